@@ -0,0 +1,303 @@
+//! Cameras viewing the terminals.
+
+use bevy::{
+    core_pipeline::{
+        clear_color::ClearColorConfig,
+        core_2d::{Camera2d, Camera2dBundle},
+    },
+    prelude::{Camera, Component, Entity, IVec2, Or, Query, Res, Transform, Vec2, With, World},
+    render::{
+        camera::{OrthographicProjection, ScalingMode},
+        view::{Layer, RenderLayers},
+    },
+    time::Time,
+};
+use bevy_ascii_terminal::{AutoCamera, Terminal};
+
+use crate::layout::Placement;
+
+/// Which camera views a terminal. Set with
+/// [`TerminalBundleBuilder::with_camera`](crate::TerminalBundleBuilder::with_camera).
+///
+/// The shared and follow cameras expect terminals using the default
+/// [`TileScaling::World`](bevy_ascii_terminal::TileScaling::World), where a tile is one world
+/// unit.
+///
+/// The terminals of each camera are on their own [`RenderLayers`], so a terminal is only
+/// rendered by the camera of its policy: the [`SharedCamera`] renders [`SharedCamera::LAYER`]
+/// and the [`FollowCamera`] renders [`FollowCamera::LAYER`]. The other terminals stay on the
+/// default layer. The cameras render in the order of their layer, and only the first one
+/// clears the window.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CameraPolicy {
+    /// Do not spawn any camera, you are responsible for viewing the terminal.
+    None,
+
+    /// Frame the terminal with the `AutoCamera` of `bevy_ascii_terminal`, rendering the
+    /// default layer. This is the default behavior.
+    #[default]
+    PerTerminal,
+
+    /// Frame the terminal, along with every other terminal using this policy, with a single
+    /// camera.
+    Shared,
+
+    /// View the terminal through the [`FollowCamera`].
+    Follow,
+}
+
+/// Marks the camera framing the terminals using [`CameraPolicy::Shared`].
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct SharedCamera;
+
+impl SharedCamera {
+    /// The render layer of the terminals using [`CameraPolicy::Shared`].
+    pub const LAYER: Layer = 1;
+}
+
+/// A camera scrolling to keep a grid position centered, for maps larger than the window.
+///
+/// The camera is spawned with the first terminal using [`CameraPolicy::Follow`], or when
+/// the backend is built if it was configured with
+/// [`BevyAsciiTerminalBackend::with_follow_camera`](crate::BevyAsciiTerminalBackend::with_follow_camera).
+/// Update its [`target`](FollowCamera::target) to move it.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct FollowCamera {
+    /// The tile to center, in world tiles.
+    pub target: IVec2,
+    /// The height of a tile of the font, in pixels.
+    pub tile_pixels: u32,
+    /// The integer zoom level, `1` drawing the font at its size.
+    pub zoom: u32,
+    /// How fast the camera catches up with its target. `0.0` moves it instantly, higher
+    /// values are faster.
+    pub smoothing: f32,
+}
+
+impl Default for FollowCamera {
+    fn default() -> Self {
+        Self {
+            target: IVec2::ZERO,
+            tile_pixels: 8,
+            zoom: 1,
+            smoothing: 0.0,
+        }
+    }
+}
+
+impl FollowCamera {
+    /// The render layer of the terminals using [`CameraPolicy::Follow`].
+    pub const LAYER: Layer = 2;
+
+    /// Create a follow camera for a font with tiles of the given height in pixels.
+    pub fn new(tile_pixels: u32) -> Self {
+        Self {
+            tile_pixels,
+            ..Default::default()
+        }
+    }
+
+    /// Set the integer zoom level. Levels below `1` are treated as `1`.
+    pub fn with_zoom(mut self, zoom: u32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Set how fast the camera catches up with its target. See [`FollowCamera::smoothing`].
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Zoom in by one level.
+    pub fn zoom_in(&mut self) {
+        self.zoom = self.zoom.max(1) + 1;
+    }
+
+    /// Zoom out by one level, down to `1`.
+    pub fn zoom_out(&mut self) {
+        self.zoom = self.zoom.saturating_sub(1).max(1);
+    }
+}
+
+/// Adds the camera required by the policy of a newly spawned terminal.
+pub(crate) fn spawn_camera(world: &mut World, terminal: Entity, policy: CameraPolicy) {
+    match policy {
+        CameraPolicy::None => (),
+        CameraPolicy::PerTerminal => {
+            world.entity_mut(terminal).insert(AutoCamera);
+        }
+        CameraPolicy::Shared => {
+            world
+                .entity_mut(terminal)
+                .insert(RenderLayers::layer(SharedCamera::LAYER));
+            let mut cameras = world.query_filtered::<(), With<SharedCamera>>();
+            if cameras.iter(world).next().is_none() {
+                world.spawn((layer_camera(SharedCamera::LAYER), SharedCamera));
+            }
+        }
+        CameraPolicy::Follow => {
+            world
+                .entity_mut(terminal)
+                .insert(RenderLayers::layer(FollowCamera::LAYER));
+            spawn_follow_camera(world, FollowCamera::default());
+        }
+    }
+}
+
+/// Spawns a follow camera, unless one already exists.
+pub(crate) fn spawn_follow_camera(world: &mut World, follow: FollowCamera) {
+    let mut cameras = world.query_filtered::<(), With<FollowCamera>>();
+    if cameras.iter(world).next().is_none() {
+        world.spawn((layer_camera(FollowCamera::LAYER), follow));
+    }
+}
+
+/// Returns a camera rendering the given layer only, ordered by its layer.
+fn layer_camera(layer: Layer) -> (Camera2dBundle, RenderLayers) {
+    let mut camera = Camera2dBundle::default();
+    camera.camera.priority = layer as isize;
+    (camera, RenderLayers::layer(layer))
+}
+
+/// Lets the [`SharedCamera`] or [`FollowCamera`] clear the window only when it is the first
+/// camera to render, so it does not erase the terminals of the cameras before it.
+pub(crate) fn clear_with_first_camera(
+    cameras: Query<&Camera>,
+    mut layer_cameras: Query<
+        (&Camera, &mut Camera2d),
+        Or<(With<SharedCamera>, With<FollowCamera>)>,
+    >,
+) {
+    let first = cameras
+        .iter()
+        .filter(|camera| camera.is_active)
+        .map(|camera| camera.priority)
+        .min();
+    for (camera, mut camera_2d) in &mut layer_cameras {
+        let is_first = first == Some(camera.priority);
+        match camera_2d.clear_color {
+            ClearColorConfig::None if is_first => camera_2d.clear_color = ClearColorConfig::Default,
+            ClearColorConfig::Default | ClearColorConfig::Custom(_) if !is_first => {
+                camera_2d.clear_color = ClearColorConfig::None;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Frames the terminals using [`CameraPolicy::Shared`] with the [`SharedCamera`].
+pub(crate) fn frame_shared_camera(
+    terminals: Query<(&CameraPolicy, &Placement, &Terminal)>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<SharedCamera>>,
+) {
+    let mut bounds: Option<(IVec2, IVec2)> = None;
+    for (_, placement, terminal) in terminals
+        .iter()
+        .filter(|(policy, _, _)| **policy == CameraPolicy::Shared)
+    {
+        let size = terminal.size().as_ivec2();
+        let min = placement.bottom_left(size);
+        let max = min + placement.outer_size(size);
+        bounds = Some(match bounds {
+            Some((bounds_min, bounds_max)) => (bounds_min.min(min), bounds_max.max(max)),
+            None => (min, max),
+        });
+    }
+    let Some((min, max)) = bounds else {
+        return;
+    };
+
+    let center = (min + max).as_vec2() / 2.0;
+    let size = (max - min).as_vec2();
+    for (mut transform, mut projection) in &mut cameras {
+        if transform.translation.truncate() != center {
+            transform.translation = center.extend(transform.translation.z);
+        }
+        if !matches!(
+            projection.scaling_mode,
+            ScalingMode::Auto { min_width, min_height } if min_width == size.x && min_height == size.y
+        ) {
+            projection.scaling_mode = ScalingMode::Auto {
+                min_width: size.x,
+                min_height: size.y,
+            };
+            projection.scale = 1.0;
+        }
+    }
+}
+
+/// Moves the [`FollowCamera`] toward its target and applies its zoom level.
+pub(crate) fn update_follow_camera(
+    time: Res<Time>,
+    mut cameras: Query<(&FollowCamera, &mut Transform, &mut OrthographicProjection)>,
+) {
+    for (follow, mut transform, mut projection) in &mut cameras {
+        let scale = 1.0 / (follow.tile_pixels.max(1) * follow.zoom.max(1)) as f32;
+        if !matches!(projection.scaling_mode, ScalingMode::WindowSize) || projection.scale != scale
+        {
+            projection.scaling_mode = ScalingMode::WindowSize;
+            projection.scale = scale;
+        }
+
+        let target = follow.target.as_vec2() + Vec2::splat(0.5);
+        let position = transform.translation.truncate();
+        if position == target {
+            continue;
+        }
+        let position = if follow.smoothing > 0.0 {
+            let t = 1.0 - (-follow.smoothing * time.delta_seconds()).exp();
+            let position = position.lerp(target, t);
+            // Snap once closer than a pixel, so the camera settles.
+            if position.distance(target) / scale < 1.0 {
+                target
+            } else {
+                position
+            }
+        } else {
+            target
+        };
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Schedule, Stage, SystemStage};
+
+    use super::*;
+
+    fn clears(world: &World, entity: Entity) -> bool {
+        !matches!(
+            world.get::<Camera2d>(entity).unwrap().clear_color,
+            ClearColorConfig::None
+        )
+    }
+
+    #[test]
+    fn only_the_first_camera_clears() {
+        let mut world = World::new();
+        let user = world.spawn(Camera2dBundle::default()).id();
+        let shared = world
+            .spawn((layer_camera(SharedCamera::LAYER), SharedCamera))
+            .id();
+        let follow = world
+            .spawn((layer_camera(FollowCamera::LAYER), FollowCamera::default()))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "update",
+            SystemStage::single_threaded().with_system(clear_with_first_camera),
+        );
+
+        schedule.run(&mut world);
+        assert!(clears(&world, user));
+        assert!(!clears(&world, shared));
+        assert!(!clears(&world, follow));
+
+        world.despawn(user);
+        schedule.run(&mut world);
+        assert!(clears(&world, shared));
+        assert!(!clears(&world, follow));
+    }
+}
@@ -0,0 +1,70 @@
+//! [`ConsoleApi`] implementation for `bevy_ascii_terminal` terminals.
+
+use bevy::prelude::{Color, IVec2};
+use bevy_ascii_terminal::Terminal;
+use brltk_common::{ConsoleApi, Rgba};
+
+#[inline]
+pub(crate) fn to_color((r, g, b, a): Rgba) -> Color {
+    Color::rgba_u8(r, g, b, a)
+}
+
+#[inline]
+pub(crate) fn to_rgba(color: Color) -> Rgba {
+    let [r, g, b, a] = color.as_rgba_f32();
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b), channel(a))
+}
+
+/// Draws on a [`Terminal`] component.
+///
+/// `bevy_ascii_terminal` puts the origin at the bottom left corner of the terminal, while
+/// [`ConsoleApi`] coordinates start at the top left corner: the `y` axis is flipped by this
+/// implementation.
+impl ConsoleApi for Terminal {
+    fn size(&self) -> (u32, u32) {
+        let size = Terminal::size(self);
+        (size.x, size.y)
+    }
+
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        let Some(xy) = grid_position(self, x, y) else {
+            return;
+        };
+
+        let tile = self.get_tile_mut(xy);
+        if let Some(glyph) = glyph {
+            tile.glyph = glyph;
+        }
+        if let Some(fore) = fore {
+            tile.fg_color = to_color(fore);
+        }
+        if let Some(back) = back {
+            tile.bg_color = to_color(back);
+        }
+    }
+
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char> {
+        grid_position(self, x, y).map(|xy| self.get_tile(xy).glyph)
+    }
+
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba> {
+        grid_position(self, x, y).map(|xy| to_rgba(self.get_tile(xy).fg_color))
+    }
+
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba> {
+        grid_position(self, x, y).map(|xy| to_rgba(self.get_tile(xy).bg_color))
+    }
+}
+
+/// Converts top left based console coordinates to the terminal grid.
+fn grid_position(term: &Terminal, x: i32, y: i32) -> Option<IVec2> {
+    ConsoleApi::contains(term, x, y).then(|| IVec2::new(x, term.height() as i32 - 1 - y))
+}
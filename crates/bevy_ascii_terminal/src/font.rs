@@ -0,0 +1,49 @@
+//! Runtime font switching.
+
+use bevy::{
+    asset::AssetServer,
+    log::warn,
+    prelude::{EventReader, Query, Res, ResMut},
+};
+use bevy_ascii_terminal::TerminalFont;
+use brltk_common::{ChangeFont, Charset, ResizePolicy, ResizeState};
+
+use crate::FollowCamera;
+
+/// Draws every terminal with the font of the latest [`ChangeFont`] event.
+///
+/// The terminals are laid out in tiles, and framed by their camera: only the
+/// [`FollowCamera`] tile size depends on the font. With [`ResizePolicy::KeepCellSize`], the
+/// policy is applied again with the cell size of the new font, which resizes the primary
+/// terminal and places the others around it. Terminals spawned afterwards use the font of
+/// their builder.
+pub(crate) fn handle_change_font(
+    mut change_font: EventReader<ChangeFont>,
+    asset_server: Res<AssetServer>,
+    mut resize_state: ResMut<ResizeState>,
+    mut terminals: Query<&mut TerminalFont>,
+    mut follow_cameras: Query<&mut FollowCamera>,
+) {
+    let Some(ChangeFont(font)) = change_font.iter().last() else {
+        return;
+    };
+    if font.grid != (16, 16) || font.charset != Charset::Cp437 {
+        warn!(
+            "bevy_ascii_terminal only supports 16x16 CP437 fonts, ignoring the font {}",
+            font.name
+        );
+        return;
+    }
+
+    let handle = asset_server.load(font.path.as_str());
+    for mut terminal_font in &mut terminals {
+        *terminal_font = TerminalFont::Custom(handle.clone());
+    }
+    for mut follow in &mut follow_cameras {
+        follow.tile_pixels = font.cell_size.1;
+    }
+    resize_state.set_cell_size(font.cell_size);
+    if matches!(resize_state.policy(), ResizePolicy::KeepCellSize) {
+        resize_state.request_resize();
+    }
+}
@@ -0,0 +1,80 @@
+//! Reports Bevy input to the backend agnostic [`ConsoleInput`].
+
+use bevy::{
+    prelude::{
+        Camera, EventReader, GlobalTransform, Input, KeyCode, MouseButton, Query, Res, ResMut, Vec2,
+    },
+    render::view::RenderLayers,
+    window::{ReceivedCharacter, Window, WindowCloseRequested, Windows},
+};
+use bevy_ascii_terminal::Terminal;
+use brltk_common::ConsoleInput;
+
+use crate::layout::Placement;
+
+/// Fills the [`ConsoleInput`] resource. The mouse position is reported in the cells of the
+/// first terminal, through the camera rendering its layer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_console_input(
+    terminals: Query<(
+        &Terminal,
+        &Placement,
+        Option<&RenderLayers>,
+        &GlobalTransform,
+    )>,
+    cameras: Query<(&Camera, &GlobalTransform, Option<&RenderLayers>)>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut close_requested: EventReader<WindowCloseRequested>,
+    mut console_input: ResMut<ConsoleInput>,
+) {
+    let mouse_position = windows.get_primary().zip(terminals.iter().next()).and_then(
+        |(window, (terminal, placement, layers, transform))| {
+            let layers = layers.copied().unwrap_or_default();
+            let (camera, camera_transform, _) =
+                cameras.iter().find(|(camera, _, camera_layers)| {
+                    camera.is_active
+                        && camera_layers
+                            .copied()
+                            .unwrap_or_default()
+                            .intersects(&layers)
+                })?;
+            let world = cursor_to_world(window, camera, camera_transform)?;
+
+            // The terminal may have been moved since it was placed.
+            let size = terminal.size().as_ivec2();
+            let offset = transform.translation().truncate() - placement.position.as_vec2();
+            let border = (placement.outer_size(size) - size) / 2;
+            let bottom_left = (placement.bottom_left(size) + border).as_vec2() + offset;
+            Some((
+                world.x - bottom_left.x,
+                size.y as f32 - (world.y - bottom_left.y),
+            ))
+        },
+    );
+
+    console_input.update_from_bevy(
+        &keys,
+        &mouse_buttons,
+        received_characters.iter().map(|event| event.char),
+        mouse_position,
+        close_requested.iter().count() > 0,
+    );
+}
+
+/// Returns the world position under the cursor, seen through the camera. The terminals use
+/// [`TileScaling::World`](bevy_ascii_terminal::TileScaling::World): a tile is one world unit.
+fn cursor_to_world(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    // The cursor starts at the bottom left corner of the window, the viewport at the top left.
+    let (min, max) = camera.logical_viewport_rect()?;
+    let viewport_position = Vec2::new(cursor.x - min.x, cursor.y - (window.height() - max.y));
+    let ray = camera.viewport_to_world(camera_transform, viewport_position)?;
+    Some(ray.origin.truncate())
+}
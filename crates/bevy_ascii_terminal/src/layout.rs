@@ -0,0 +1,294 @@
+//! Placement of terminals relative to each other.
+
+use bevy::prelude::{Component, IVec2};
+use bevy_ascii_terminal::Pivot;
+
+/// Where to place a terminal relative to another, named, terminal. See
+/// [`TerminalBundleBuilder::with_layout`](crate::TerminalBundleBuilder::with_layout).
+///
+/// The placement is computed from the size of the other terminal when the terminal is
+/// spawned, and again when the [`ResizePolicy`](brltk_common::ResizePolicy) resizes the
+/// primary terminal. It is not updated if the other terminal is moved or resized by hand.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub enum TerminalLayout {
+    /// Right of the named terminal, with their top edges aligned.
+    RightOf(String),
+    /// Left of the named terminal, with their top edges aligned.
+    LeftOf(String),
+    /// Above the named terminal, with their left edges aligned.
+    Above(String),
+    /// Below the named terminal, with their left edges aligned.
+    Below(String),
+}
+
+impl TerminalLayout {
+    /// A side panel, right of the named terminal.
+    pub fn side_panel(of: impl Into<String>) -> Self {
+        Self::RightOf(of.into())
+    }
+
+    /// A log, below the named terminal.
+    pub fn bottom_log(of: impl Into<String>) -> Self {
+        Self::Below(of.into())
+    }
+
+    /// Returns the name of the terminal this layout is relative to.
+    pub fn target(&self) -> &str {
+        match self {
+            Self::RightOf(name) | Self::LeftOf(name) | Self::Above(name) | Self::Below(name) => {
+                name
+            }
+        }
+    }
+
+    /// Returns the bottom left corner of a terminal of the given size, border included,
+    /// placed relative to the terminal at `target` of size `target_size`.
+    pub(crate) fn bottom_left(&self, target: &Placement, target_size: IVec2, size: IVec2) -> IVec2 {
+        let min = target.bottom_left(target_size);
+        let max = min + target.outer_size(target_size);
+        match self {
+            Self::RightOf(_) => IVec2::new(max.x, max.y - size.y),
+            Self::LeftOf(_) => IVec2::new(min.x - size.x, max.y - size.y),
+            Self::Above(_) => IVec2::new(min.x, max.y),
+            Self::Below(_) => IVec2::new(min.x, min.y - size.y),
+        }
+    }
+}
+
+/// The position and pivot a terminal was spawned with, and whether it has a border, used
+/// to lay out other terminals around it.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct Placement {
+    pub(crate) position: IVec2,
+    pub(crate) pivot: Pivot,
+    pub(crate) border: bool,
+}
+
+impl Placement {
+    /// Returns the size of a terminal of the given size once its border is drawn. A border
+    /// takes one tile on each side.
+    pub(crate) fn outer_size(&self, size: IVec2) -> IVec2 {
+        size + IVec2::splat(if self.border { 2 } else { 0 })
+    }
+
+    /// Returns the bottom left corner of a terminal of the given size, border included.
+    /// Centered terminals of odd sizes are rounded down.
+    pub(crate) fn bottom_left(&self, size: IVec2) -> IVec2 {
+        let (x, y) = match self.pivot {
+            Pivot::TopLeft => (0, 2),
+            Pivot::TopCenter => (1, 2),
+            Pivot::TopRight => (2, 2),
+            Pivot::LeftCenter => (0, 1),
+            Pivot::Center => (1, 1),
+            Pivot::RightCenter => (2, 1),
+            Pivot::BottomLeft => (0, 0),
+            Pivot::BottomCenter => (1, 0),
+            Pivot::BottomRight => (2, 0),
+        };
+        self.position - IVec2::new(x, y) * self.outer_size(size) / 2
+    }
+}
+
+/// Marks the terminal resized by the [`ResizePolicy`](brltk_common::ResizePolicy): the first
+/// terminal spawned that is not laid out relative to another.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub(crate) struct PrimaryTerminal;
+
+/// Returns the size of the primary terminal filling `window_size` tiles, leaving room for
+/// its border and for the terminals laid out next to it, given with their outer size.
+pub(crate) fn primary_size<'a>(
+    window_size: IVec2,
+    primary: &Placement,
+    panels: impl IntoIterator<Item = (&'a TerminalLayout, IVec2)>,
+) -> IVec2 {
+    let mut size = window_size - primary.outer_size(IVec2::ZERO);
+    for (layout, panel_size) in panels {
+        match layout {
+            TerminalLayout::RightOf(_) | TerminalLayout::LeftOf(_) => size.x -= panel_size.x,
+            TerminalLayout::Above(_) | TerminalLayout::Below(_) => size.y -= panel_size.y,
+        }
+    }
+    size.max(IVec2::ONE)
+}
+
+/// A terminal to lay out: its name, its layout, its size in tiles and its placement.
+#[derive(Debug, Clone)]
+pub(crate) struct LayoutEntry {
+    pub(crate) name: Option<String>,
+    pub(crate) layout: Option<TerminalLayout>,
+    pub(crate) size: IVec2,
+    pub(crate) placement: Placement,
+}
+
+/// Places every laid out terminal next to its target, once the target itself is placed. A
+/// terminal laid out relative to a terminal that does not exist keeps its placement.
+pub(crate) fn apply_layouts(entries: &mut [LayoutEntry]) {
+    let mut placed: Vec<bool> = entries.iter().map(|entry| entry.layout.is_none()).collect();
+    loop {
+        let mut progress = false;
+        for index in 0..entries.len() {
+            let Some(layout) = entries[index].layout.as_ref().filter(|_| !placed[index]) else {
+                continue;
+            };
+            let target = entries
+                .iter()
+                .position(|entry| entry.name.as_deref() == Some(layout.target()));
+            match target {
+                Some(target) if !placed[target] => continue,
+                Some(target) => {
+                    let target = &entries[target];
+                    let entry = &entries[index];
+                    let size = entry.placement.outer_size(entry.size);
+                    let position = layout.bottom_left(&target.placement, target.size, size);
+                    entries[index].placement.position = position;
+                    entries[index].placement.pivot = Pivot::BottomLeft;
+                }
+                None => (),
+            }
+            placed[index] = true;
+            progress = true;
+        }
+        if !progress {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: IVec2 = IVec2::new(40, 20);
+
+    fn map() -> Placement {
+        Placement {
+            position: IVec2::ZERO,
+            pivot: Pivot::Center,
+            border: false,
+        }
+    }
+
+    #[test]
+    fn bottom_left_follows_the_pivot() {
+        assert_eq!(map().bottom_left(MAP), IVec2::new(-20, -10));
+        let top_right = Placement {
+            pivot: Pivot::TopRight,
+            ..map()
+        };
+        assert_eq!(top_right.bottom_left(MAP), IVec2::new(-40, -20));
+    }
+
+    #[test]
+    fn bottom_left_includes_the_border() {
+        let bordered = Placement {
+            border: true,
+            ..map()
+        };
+        assert_eq!(bordered.outer_size(MAP), IVec2::new(42, 22));
+        assert_eq!(bordered.bottom_left(MAP), IVec2::new(-21, -11));
+    }
+
+    #[test]
+    fn places_next_to_the_target() {
+        let size = IVec2::new(10, 5);
+        let cases = [
+            (TerminalLayout::side_panel("map"), IVec2::new(20, 5)),
+            (TerminalLayout::LeftOf("map".into()), IVec2::new(-30, 5)),
+            (TerminalLayout::Above("map".into()), IVec2::new(-20, 10)),
+            (TerminalLayout::bottom_log("map"), IVec2::new(-20, -15)),
+        ];
+        for (layout, expected) in cases {
+            assert_eq!(
+                layout.bottom_left(&map(), MAP, size),
+                expected,
+                "{layout:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn places_next_to_the_border_of_the_target() {
+        let bordered = Placement {
+            border: true,
+            ..map()
+        };
+        let side_panel = TerminalLayout::side_panel("map");
+        assert_eq!(
+            side_panel.bottom_left(&bordered, MAP, IVec2::new(10, 5)),
+            IVec2::new(21, 6)
+        );
+    }
+
+    fn entry(name: &str, layout: Option<TerminalLayout>, size: IVec2) -> LayoutEntry {
+        LayoutEntry {
+            name: Some(name.to_string()),
+            layout,
+            size,
+            placement: map(),
+        }
+    }
+
+    #[test]
+    fn primary_size_leaves_room_for_the_panels() {
+        let side_panel = TerminalLayout::side_panel("map");
+        let log = TerminalLayout::bottom_log("map");
+        let panels = [
+            (&side_panel, IVec2::new(20, 30)),
+            (&log, IVec2::new(80, 10)),
+        ];
+        assert_eq!(
+            primary_size(IVec2::new(100, 40), &map(), panels),
+            IVec2::new(80, 30)
+        );
+
+        let bordered = Placement {
+            border: true,
+            ..map()
+        };
+        assert_eq!(
+            primary_size(IVec2::new(100, 40), &bordered, []),
+            IVec2::new(98, 38)
+        );
+        assert_eq!(primary_size(IVec2::new(10, 10), &map(), panels), IVec2::ONE);
+    }
+
+    #[test]
+    fn layouts_follow_their_target() {
+        let mut entries = [
+            entry(
+                "status",
+                Some(TerminalLayout::bottom_log("log")),
+                IVec2::new(10, 2),
+            ),
+            entry(
+                "log",
+                Some(TerminalLayout::bottom_log("map")),
+                IVec2::new(40, 5),
+            ),
+            entry("map", None, MAP),
+            entry(
+                "lost",
+                Some(TerminalLayout::side_panel("missing")),
+                IVec2::new(5, 5),
+            ),
+        ];
+        apply_layouts(&mut entries);
+
+        assert_eq!(entries[1].placement.position, IVec2::new(-20, -15));
+        assert!(matches!(entries[1].placement.pivot, Pivot::BottomLeft));
+        assert_eq!(entries[0].placement.position, IVec2::new(-20, -17));
+        assert_eq!(entries[2].placement.position, IVec2::ZERO);
+        assert_eq!(entries[3].placement.position, IVec2::ZERO);
+        assert!(matches!(entries[3].placement.pivot, Pivot::Center));
+    }
+
+    #[test]
+    fn layout_cycles_stop() {
+        let mut entries = [
+            entry("a", Some(TerminalLayout::side_panel("b")), IVec2::ONE),
+            entry("b", Some(TerminalLayout::side_panel("a")), IVec2::ONE),
+        ];
+        apply_layouts(&mut entries);
+        assert_eq!(entries[0].placement.position, IVec2::ZERO);
+    }
+}
@@ -6,11 +6,31 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use bevy_ascii_terminal::{AutoCamera, Terminal, TerminalBundle, TerminalPlugin};
-use brltk_common::Backend;
+use bevy::{
+    input::InputSystem,
+    prelude::{CoreStage, IntoSystemDescriptor},
+    transform::TransformSystem,
+};
+use bevy_ascii_terminal::TerminalPlugin;
+use brltk_common::{
+    track_window_lifecycle, Backend, BackendCapabilities, ChangeFont, ConsoleInput,
+    ConsoleInputSystem, ResizePlugin, ResizePolicy,
+};
 
+use crate::resize::TerminalConsoles;
+
+mod camera;
+mod console;
+mod font;
+mod input;
+mod layout;
+mod resize;
 mod term_builder;
+mod terminals;
+pub use camera::{CameraPolicy, FollowCamera, SharedCamera};
+pub use layout::TerminalLayout;
 pub use term_builder::TerminalBundleBuilder;
+pub use terminals::{DespawnTerminal, SpawnTerminal, TerminalName, Terminals};
 
 /// Re-export of the Doryen library types.
 pub mod ascii_terminal {
@@ -21,51 +41,103 @@ pub mod ascii_terminal {
 #[derive(Default)]
 pub struct BevyAsciiTerminalBackend {
     terms: Vec<TerminalBundleBuilder>,
+    resize_policy: ResizePolicy,
+    follow_camera: Option<FollowCamera>,
 }
 
 impl Backend for BevyAsciiTerminalBackend {
     fn build(&self, app: &mut bevy::app::App) {
         // Add the terminal plugin.
-        app.add_plugin(TerminalPlugin);
+        app.add_plugin(TerminalPlugin)
+            .init_resource::<ConsoleInput>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                crate::input::update_console_input
+                    .label(ConsoleInputSystem)
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, track_window_lifecycle)
+            .add_plugin(ResizePlugin::<TerminalConsoles>::new(self.resize_policy))
+            .add_event::<SpawnTerminal>()
+            .add_event::<DespawnTerminal>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                crate::terminals::handle_terminal_events,
+            )
+            .add_event::<ChangeFont>()
+            .add_system_to_stage(CoreStage::PreUpdate, crate::font::handle_change_font)
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                crate::camera::frame_shared_camera.before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                crate::camera::update_follow_camera.before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                crate::camera::clear_with_first_camera,
+            );
+
+        if let Some(follow_camera) = self.follow_camera {
+            crate::camera::spawn_follow_camera(&mut app.world, follow_camera);
+        }
 
         // Add the terminals.
         for term_builder in &self.terms {
-            let term = Terminal::from((*term_builder).clone());
-            let mut bundle = TerminalBundle::from(term);
-
-            bundle = bundle
-                .with_depth(term_builder.depth)
-                .with_size(term_builder.size);
-
-            if let Some(pivot) = term_builder.pivot {
-                bundle = bundle.with_pivot(pivot);
-            }
-
-            if let Some(scaling) = term_builder.scaling {
-                bundle = bundle.with_tile_scaling(scaling);
-            }
-
-            if let Some(font) = &term_builder.font {
-                bundle = bundle.with_font(font.clone());
-            }
-
-            if let Some(pos) = &term_builder.pos {
-                bundle = bundle.with_position(*pos);
-            }
+            crate::terminals::spawn_terminal(&mut app.world, term_builder);
+        }
+    }
 
-            if term_builder.auto_camera {
-                app.world.spawn((bundle, AutoCamera));
-            } else {
-                app.world.spawn(bundle);
-            }
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            name: "ascii_terminal",
+            subcell: false,
+            // The terminals are meshes drawn in the transparent 2d pass.
+            alpha_blending: true,
+            true_color: true,
+            named_colors: false,
+            // Each terminal is a layer, and a clear tile with a transparent background
+            // leaves its unset cells transparent.
+            layers: true,
+            sparse_layers: true,
+            multiple_fonts: true,
+            runtime_font_change: true,
+            runtime_resize: matches!(
+                self.resize_policy,
+                ResizePolicy::KeepCellSize | ResizePolicy::Callback(_)
+            ),
+            borders: true,
+            windowed: true,
         }
     }
 }
 
 impl BevyAsciiTerminalBackend {
-    /// Create a new terminal builder.
+    /// Add a terminal, spawned when the backend is built. Terminals are spawned in the order
+    /// they are added, so a terminal laid out relative to another must be added after it.
     pub fn with_terminal(mut self, terminal: TerminalBundleBuilder) -> Self {
         self.terms.push(terminal);
         self
     }
+
+    /// Set the [`ResizePolicy`] of the terminals.
+    ///
+    /// The terminals are framed by their camera, which keeps their aspect ratio: both
+    /// [`ResizePolicy::KeepCellCount`] and [`ResizePolicy::Letterbox`] letterbox them.
+    ///
+    /// [`ResizePolicy::KeepCellSize`] resizes the primary terminal, the first one added
+    /// without a [`TerminalLayout`], to fill the window next to the terminals laid out
+    /// around it. The laid out terminals keep their size and are placed around it again.
+    pub fn with_resize_policy(mut self, resize_policy: ResizePolicy) -> Self {
+        self.resize_policy = resize_policy;
+        self
+    }
+
+    /// Spawn a [`FollowCamera`] with the given settings, for the terminals using
+    /// [`CameraPolicy::Follow`].
+    pub fn with_follow_camera(mut self, follow_camera: FollowCamera) -> Self {
+        self.follow_camera = Some(follow_camera);
+        self
+    }
 }
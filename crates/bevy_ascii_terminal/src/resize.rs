@@ -0,0 +1,157 @@
+//! Window resize handling.
+
+use bevy::prelude::{Entity, IVec2, Transform, With, World};
+use bevy_ascii_terminal::Terminal;
+use brltk_common::ResizeConsoles;
+
+use crate::{
+    layout::{apply_layouts, primary_size, LayoutEntry, Placement, PrimaryTerminal},
+    TerminalLayout, TerminalName,
+};
+
+/// Resizes the primary terminal to fill the window, next to the terminals laid out around
+/// it, then places the laid out terminals again. Reports the size of the primary terminal.
+pub(crate) struct TerminalConsoles;
+
+impl ResizeConsoles for TerminalConsoles {
+    fn console_size(world: &mut World) -> (u32, u32) {
+        let mut terminals = world.query_filtered::<&Terminal, With<PrimaryTerminal>>();
+        let size = terminals.iter(world).next().map(|terminal| {
+            let size = terminal.size();
+            (size.x, size.y)
+        });
+        size.unwrap_or_default()
+    }
+
+    fn resize_consoles(world: &mut World, console_size: (u32, u32)) {
+        let mut terminals = world.query::<(
+            Entity,
+            Option<&TerminalName>,
+            Option<&TerminalLayout>,
+            &Placement,
+            &Terminal,
+            Option<&PrimaryTerminal>,
+        )>();
+        let mut primary = None;
+        let mut entities = Vec::new();
+        let mut entries = Vec::new();
+        for (entity, name, layout, placement, terminal, is_primary) in terminals.iter(world) {
+            if is_primary.is_some() {
+                primary = Some(entries.len());
+            }
+            entities.push(entity);
+            entries.push(LayoutEntry {
+                name: name.map(|name| name.0.clone()),
+                layout: layout.cloned(),
+                size: terminal.size().as_ivec2(),
+                placement: *placement,
+            });
+        }
+        let Some(primary) = primary else {
+            return;
+        };
+
+        let primary_name = entries[primary].name.clone();
+        let panels = entries.iter().filter_map(|entry| {
+            let layout = entry.layout.as_ref()?;
+            (primary_name.as_deref() == Some(layout.target()))
+                .then(|| (layout, entry.placement.outer_size(entry.size)))
+        });
+        let window_size = IVec2::new(console_size.0 as i32, console_size.1 as i32);
+        let size = primary_size(window_size, &entries[primary].placement, panels);
+        entries[primary].size = size;
+        if let Some(mut terminal) = world.get_mut::<Terminal>(entities[primary]) {
+            terminal.resize([size.x as u32, size.y as u32]);
+        }
+
+        apply_layouts(&mut entries);
+        for (entity, entry) in entities.into_iter().zip(entries) {
+            if entry.layout.is_none() {
+                continue;
+            }
+            let mut entity = world.entity_mut(entity);
+            if let Some(mut placement) = entity.get_mut::<Placement>() {
+                *placement = entry.placement;
+            }
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                let position = entry.placement.position.as_vec2();
+                transform.translation = position.extend(transform.translation.z);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec3;
+    use bevy_ascii_terminal::Pivot;
+
+    use super::*;
+
+    fn spawn(
+        world: &mut World,
+        name: &str,
+        layout: Option<TerminalLayout>,
+        size: [u32; 2],
+        placement: Placement,
+    ) -> Entity {
+        let mut entity = world.spawn((
+            Terminal::new(size),
+            placement,
+            Transform::from_translation(placement.position.as_vec2().extend(1.0)),
+            TerminalName(name.to_string()),
+        ));
+        match layout {
+            Some(layout) => entity.insert(layout),
+            None => entity.insert(PrimaryTerminal),
+        };
+        entity.id()
+    }
+
+    #[test]
+    fn the_side_panel_stays_next_to_the_map() {
+        let mut world = World::new();
+        let map = spawn(
+            &mut world,
+            "map",
+            None,
+            [40, 20],
+            Placement {
+                position: IVec2::ZERO,
+                pivot: Pivot::Center,
+                border: false,
+            },
+        );
+        let panel = spawn(
+            &mut world,
+            "panel",
+            Some(TerminalLayout::side_panel("map")),
+            [20, 20],
+            Placement {
+                position: IVec2::new(20, -10),
+                pivot: Pivot::BottomLeft,
+                border: false,
+            },
+        );
+
+        TerminalConsoles::resize_consoles(&mut world, (100, 30));
+
+        assert_eq!(
+            world.get::<Terminal>(map).unwrap().size().to_array(),
+            [80, 30]
+        );
+        assert_eq!(
+            world.get::<Terminal>(panel).unwrap().size().to_array(),
+            [20, 20]
+        );
+        assert_eq!(
+            world.get::<Placement>(panel).unwrap().position,
+            IVec2::new(40, -5)
+        );
+        assert_eq!(
+            world.get::<Transform>(panel).unwrap().translation,
+            Vec3::new(40.0, -5.0, 1.0)
+        );
+        assert_eq!(TerminalConsoles::console_size(&mut world), (80, 30));
+    }
+}
@@ -3,21 +3,28 @@ use bevy_ascii_terminal::{
     Border, GridPoint, Pivot, Size2d, Terminal, TerminalFont, Tile, TileScaling,
 };
 
+use brltk_common::FontRegistry;
+
+use crate::{CameraPolicy, TerminalLayout};
+
 /// A builder for creating a terminal.
 #[derive(Debug, Clone)]
 pub struct TerminalBundleBuilder {
     pub(crate) depth: i32,
     pub(crate) size: UVec2,
 
-    /// Add an auto camera to the terminal.
-    pub(crate) auto_camera: bool,
+    pub(crate) camera: CameraPolicy,
 
     pub(crate) pos: Option<IVec2>,
     pub(crate) pivot: Option<Pivot>,
     pub(crate) border: Option<Border>,
     pub(crate) clear_tile: Option<Tile>,
     pub(crate) font: Option<TerminalFont>,
+    pub(crate) font_path: Option<String>,
     pub(crate) scaling: Option<TileScaling>,
+
+    pub(crate) name: Option<String>,
+    pub(crate) layout: Option<TerminalLayout>,
 }
 
 impl Default for TerminalBundleBuilder {
@@ -26,11 +33,14 @@ impl Default for TerminalBundleBuilder {
             depth: 0,
             pos: None,
             font: None,
+            font_path: None,
             pivot: None,
             border: None,
             scaling: None,
             clear_tile: None,
-            auto_camera: true,
+            name: None,
+            layout: None,
+            camera: CameraPolicy::PerTerminal,
             size: UVec2::new(80, 50),
         }
     }
@@ -42,9 +52,14 @@ impl TerminalBundleBuilder {
         Self::default().with_size(size)
     }
 
-    /// Add an auto camera to the terminal.
-    pub fn with_auto_camera(mut self) -> Self {
-        self.auto_camera = true;
+    /// Add an auto camera to the terminal. This is the default [`CameraPolicy`].
+    pub fn with_auto_camera(self) -> Self {
+        self.with_camera(CameraPolicy::PerTerminal)
+    }
+
+    /// Set which camera views the terminal.
+    pub fn with_camera(mut self, camera: CameraPolicy) -> Self {
+        self.camera = camera;
         self
     }
 
@@ -81,6 +96,22 @@ impl TerminalBundleBuilder {
     /// Set the initial font of the terminal.
     pub fn with_font(mut self, font: TerminalFont) -> Self {
         self.font = Some(font);
+        self.font_path = None;
+        self
+    }
+
+    /// Set the initial font of the terminal to a font of the [`FontRegistry`], loaded by the
+    /// [`AssetServer`](bevy::asset::AssetServer) when the terminal is spawned.
+    ///
+    /// `bevy_ascii_terminal` only supports the fonts of 16 by 16 glyphs in the
+    /// [`Charset::Cp437`](brltk_common::Charset::Cp437) order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no font is registered under this name.
+    pub fn with_registered_font(mut self, registry: &FontRegistry, name: &str) -> Self {
+        self.font_path = Some(registry.expect(name).path.clone());
+        self.font = None;
         self
     }
 
@@ -95,6 +126,20 @@ impl TerminalBundleBuilder {
         self.clear_tile = Some(clear_tile.into());
         self
     }
+
+    /// Name the terminal, to look it up through [`Terminals`](crate::Terminals) or to lay
+    /// out other terminals relative to it.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Place the terminal relative to another named terminal. This overrides the position
+    /// and pivot of the terminal, as long as the other terminal is spawned first.
+    pub fn with_layout(mut self, layout: TerminalLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
 }
 
 impl From<TerminalBundleBuilder> for Terminal {
@@ -0,0 +1,176 @@
+//! Named terminals, spawned and despawned at runtime.
+
+use bevy::{
+    asset::AssetServer,
+    ecs::system::SystemParam,
+    hierarchy::DespawnRecursiveExt,
+    prelude::{Component, Entity, Events, Mut, Query, With, World},
+};
+use bevy_ascii_terminal::{Pivot, Terminal, TerminalBundle, TerminalFont};
+
+use crate::{
+    layout::{Placement, PrimaryTerminal},
+    TerminalBundleBuilder,
+};
+
+/// The name given to a terminal with
+/// [`TerminalBundleBuilder::with_name`](crate::TerminalBundleBuilder::with_name).
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TerminalName(pub String);
+
+/// Looks up the named terminals.
+///
+/// ```ignore
+/// fn draw_log(mut terminals: Terminals) {
+///     if let Some(mut log) = terminals.get_mut("log") {
+///         log.put_string([0, 0], "You hit the orc.");
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct Terminals<'w, 's> {
+    terminals: Query<'w, 's, (Entity, &'static TerminalName, &'static mut Terminal)>,
+}
+
+impl<'w, 's> Terminals<'w, 's> {
+    /// Returns the entity of the terminal with the given name.
+    pub fn entity(&self, name: &str) -> Option<Entity> {
+        self.terminals
+            .iter()
+            .find(|(_, n, _)| n.0 == name)
+            .map(|(entity, _, _)| entity)
+    }
+
+    /// Returns the terminal with the given name.
+    pub fn get(&self, name: &str) -> Option<&Terminal> {
+        self.terminals
+            .iter()
+            .find(|(_, n, _)| n.0 == name)
+            .map(|(_, _, terminal)| terminal)
+    }
+
+    /// Returns the terminal with the given name, mutably.
+    pub fn get_mut(&mut self, name: &str) -> Option<Mut<'_, Terminal>> {
+        self.terminals
+            .iter_mut()
+            .find(|(_, n, _)| n.0 == name)
+            .map(|(_, _, terminal)| terminal)
+    }
+
+    /// Returns an iterator over the names of the terminals.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.terminals.iter().map(|(_, name, _)| name.0.as_str())
+    }
+}
+
+/// Send this event to spawn a terminal at runtime. The terminal is spawned at the start of
+/// the next frame, and can then be looked up by name through [`Terminals`].
+#[derive(Debug, Clone)]
+pub struct SpawnTerminal(pub TerminalBundleBuilder);
+
+/// Send this event to despawn the terminal with the given name, and its children.
+#[derive(Debug, Clone)]
+pub struct DespawnTerminal(pub String);
+
+/// Spawns and despawns the terminals requested through [`SpawnTerminal`] and
+/// [`DespawnTerminal`] events.
+pub(crate) fn handle_terminal_events(world: &mut World) {
+    let despawned: Vec<_> = world
+        .resource_mut::<Events<DespawnTerminal>>()
+        .drain()
+        .collect();
+    for DespawnTerminal(name) in despawned {
+        let mut terminals = world.query::<(Entity, &TerminalName)>();
+        let entity = terminals
+            .iter(world)
+            .find(|(_, n)| n.0 == name)
+            .map(|(entity, _)| entity);
+        if let Some(entity) = entity {
+            world.entity_mut(entity).despawn_recursive();
+        }
+    }
+
+    let spawned: Vec<_> = world
+        .resource_mut::<Events<SpawnTerminal>>()
+        .drain()
+        .collect();
+    for SpawnTerminal(builder) in spawned {
+        spawn_terminal(world, &builder);
+    }
+}
+
+/// Spawns the terminal described by the builder. A terminal laid out relative to a terminal
+/// that does not exist is spawned at its own position. The first terminal spawned without a
+/// layout is the primary terminal, resized by the resize policy.
+pub(crate) fn spawn_terminal(world: &mut World, builder: &TerminalBundleBuilder) -> Entity {
+    let term = Terminal::from(builder.clone());
+    let mut bundle = TerminalBundle::from(term)
+        .with_depth(builder.depth)
+        .with_size(builder.size);
+
+    let mut placement = Placement {
+        position: builder.pos.unwrap_or_default(),
+        // Terminals are centered on their position unless told otherwise.
+        pivot: builder.pivot.unwrap_or(Pivot::Center),
+        border: builder.border.is_some(),
+    };
+    let mut pivot = builder.pivot;
+    let mut position = builder.pos;
+
+    if let Some(layout) = &builder.layout {
+        let mut terminals = world.query::<(&TerminalName, &Placement, &Terminal)>();
+        let target = terminals
+            .iter(world)
+            .find(|(name, _, _)| name.0 == layout.target())
+            .map(|(_, target, terminal)| (*target, terminal.size().as_ivec2()));
+        if let Some((target, target_size)) = target {
+            let size = placement.outer_size(builder.size.as_ivec2());
+            pivot = Some(Pivot::BottomLeft);
+            position = Some(layout.bottom_left(&target, target_size, size));
+        }
+    }
+
+    if let Some(pivot) = pivot {
+        bundle = bundle.with_pivot(pivot);
+        placement.pivot = pivot;
+    }
+
+    if let Some(position) = position {
+        bundle = bundle.with_position(position);
+        placement.position = position;
+    }
+
+    if let Some(scaling) = builder.scaling {
+        bundle = bundle.with_tile_scaling(scaling);
+    }
+
+    if let Some(font) = &builder.font {
+        bundle = bundle.with_font(font.clone());
+    } else if let Some(font_path) = &builder.font_path {
+        if let Some(asset_server) = world.get_resource::<AssetServer>() {
+            bundle = bundle.with_font(TerminalFont::Custom(asset_server.load(font_path.as_str())));
+        }
+    }
+
+    let has_primary = world
+        .query_filtered::<(), With<PrimaryTerminal>>()
+        .iter(world)
+        .next()
+        .is_some();
+    let mut entity = world.spawn((bundle, placement, builder.camera));
+    if let Some(name) = &builder.name {
+        entity.insert(TerminalName(name.clone()));
+    }
+    match &builder.layout {
+        Some(layout) => {
+            entity.insert(layout.clone());
+        }
+        None if !has_primary => {
+            entity.insert(PrimaryTerminal);
+        }
+        None => (),
+    }
+    let entity = entity.id();
+    crate::camera::spawn_camera(world, entity, builder.camera);
+    entity
+}
@@ -0,0 +1,128 @@
+//! [`ConsoleApi`] implementation for the `bracket-lib` layers.
+
+use bevy::prelude::Resource;
+use bracket_bevy::prelude::*;
+use brltk_common::{Cell, CellBuffer, ConsoleApi, Rgba};
+
+#[inline]
+fn to_rgba((r, g, b, a): Rgba) -> RGBA {
+    RGBA::from_u8(r, g, b, a)
+}
+
+/// The cells drawn on each layer through a [`BracketLayerConsole`].
+///
+/// `bracket-bevy` consoles are write only: every cell drawn through the [`ConsoleApi`] is
+/// also kept here, so that it can be read back, partially updated and blitted from.
+///
+/// ```ignore
+/// fn draw(ctx: Res<BracketContext>, mut cells: ResMut<BracketCells>) {
+///     let mut console = cells.layer(&ctx, 0);
+///     console.print(1, 1, "Hello", TextAlign::Left, Some((255, 255, 0, 255)), None);
+/// }
+/// ```
+#[derive(Default, Debug, Clone, Resource)]
+pub struct BracketCells {
+    layers: Vec<CellBuffer>,
+}
+
+impl BracketCells {
+    /// Makes the layer at the given index the active console of the [`BracketContext`], and
+    /// returns a [`ConsoleApi`] drawing on it.
+    ///
+    /// The cells of the layer are forgotten when its size changes, for instance with
+    /// [`TerminalScalingMode::ResizeTerminals`](bracket_bevy::TerminalScalingMode).
+    pub fn layer<'a>(
+        &'a mut self,
+        ctx: &'a BracketContext,
+        index: usize,
+    ) -> BracketLayerConsole<'a> {
+        ctx.set_active_console(index);
+        let (width, height) = ctx.get_char_size();
+        if self.layers.len() <= index {
+            self.layers.resize_with(index + 1, || CellBuffer::new(0, 0));
+        }
+        let cells = &mut self.layers[index];
+        if cells.size() != (width, height) {
+            cells.resize(width, height);
+        }
+
+        BracketLayerConsole { ctx, cells }
+    }
+
+    /// Returns the cells drawn on the layer at the given index, if any.
+    pub fn get(&self, index: usize) -> Option<&CellBuffer> {
+        self.layers.get(index)
+    }
+}
+
+/// Draws on a layer of the [`BracketContext`]. See [`BracketCells::layer`].
+///
+/// Cells drawn with the [`BracketContext`] directly are not known to the `get_*` methods,
+/// which return the cells drawn through this console only, starting with blank cells.
+pub struct BracketLayerConsole<'a> {
+    ctx: &'a BracketContext,
+    cells: &'a mut CellBuffer,
+}
+
+impl<'a> BracketLayerConsole<'a> {
+    fn draw_cell(&self, x: i32, y: i32, cell: Cell) {
+        self.ctx.set(
+            x,
+            y,
+            to_rgba(cell.fore),
+            to_rgba(cell.back),
+            to_cp437(cell.glyph),
+        );
+    }
+}
+
+impl<'a> ConsoleApi for BracketLayerConsole<'a> {
+    fn size(&self) -> (u32, u32) {
+        self.cells.size()
+    }
+
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        self.cells.put_cell(x, y, glyph, fore, back);
+        if let Some(&cell) = self.cells.cell(x, y) {
+            self.draw_cell(x, y, cell);
+        }
+    }
+
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char> {
+        self.cells.get_glyph(x, y)
+    }
+
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.cells.get_fore(x, y)
+    }
+
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.cells.get_back(x, y)
+    }
+
+    fn clear(&mut self, fore: Option<Rgba>, back: Option<Rgba>, glyph: Option<char>) {
+        self.cells.clear(fore, back, glyph);
+
+        // `cls` draws blank white on black cells, much faster than setting every cell.
+        if self
+            .cells
+            .cells()
+            .iter()
+            .all(|cell| *cell == Cell::default())
+        {
+            self.ctx.cls();
+            return;
+        }
+        let width = self.cells.width() as usize;
+        for (index, &cell) in self.cells.cells().iter().enumerate() {
+            self.draw_cell((index % width) as i32, (index / width) as i32, cell);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Runtime font switching.
+
+use bevy::{
+    asset::{AssetServer, Assets, Handle},
+    log::warn,
+    prelude::{EventReader, Image, Res, ResMut, Resource},
+    sprite::ColorMaterial,
+    window::Windows,
+};
+use bracket_bevy::TerminalScalingMode;
+use brltk_common::ChangeFont;
+
+use crate::term::{TerminalBuilderFont, TerminalLayer};
+
+/// The fonts of the terminal, as currently drawn.
+#[derive(Resource)]
+pub(crate) struct BracketFonts {
+    fonts: Vec<TerminalBuilderFont>,
+    scaling_mode: TerminalScalingMode,
+    /// The font index and the size in cells of the bottom layer.
+    bottom_layer: Option<(usize, (i32, i32))>,
+}
+
+impl BracketFonts {
+    pub(crate) fn new(
+        fonts: &[TerminalBuilderFont],
+        layers: &[TerminalLayer],
+        scaling_mode: TerminalScalingMode,
+    ) -> Self {
+        let bottom_layer = layers.first().map(|layer| match *layer {
+            TerminalLayer::Simple {
+                font_index,
+                width,
+                height,
+                ..
+            }
+            | TerminalLayer::Sparse {
+                font_index,
+                width,
+                height,
+                ..
+            } => (font_index, (width, height)),
+        });
+        Self {
+            fonts: fonts.to_vec(),
+            scaling_mode,
+            bottom_layer,
+        }
+    }
+}
+
+/// Draws the layers with the font of the latest [`ChangeFont`] event.
+///
+/// `bracket-bevy` does not let its fonts be replaced: the texture of the materials drawing
+/// the font in use, the font of the bottom layer, is swapped instead. Layers drawn with
+/// another font, like a smaller font for the interface, keep it. The glyphs are looked up
+/// with the grid of the font in use, so fonts with another grid are ignored. With
+/// [`TerminalScalingMode::Stretch`], the window is resized to draw the cells of the bottom
+/// layer at the cell size of the new font. With [`TerminalScalingMode::ResizeTerminals`],
+/// `bracket-bevy` keeps laying out the layers with the cell size of the initial fonts.
+pub(crate) fn handle_change_font(
+    mut change_font: EventReader<ChangeFont>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut fonts: ResMut<BracketFonts>,
+    mut windows: ResMut<Windows>,
+) {
+    let Some(ChangeFont(font)) = change_font.iter().last() else {
+        return;
+    };
+    let Some((font_index, (width, height))) = fonts.bottom_layer else {
+        return;
+    };
+    let Some(current) = fonts.fonts.get_mut(font_index) else {
+        return;
+    };
+    if (u32::from(current.chars_per_row), u32::from(current.n_rows)) != font.grid {
+        warn!(
+            "The font {} does not have the {}x{} glyph grid of {}, ignoring it",
+            font.name, current.chars_per_row, current.n_rows, current.filename
+        );
+        return;
+    }
+
+    let previous = asset_server.get_handle::<Image, _>(current.filename.as_str());
+    let texture: Handle<Image> = asset_server.load(font.path.as_str());
+    let font_materials: Vec<_> = materials
+        .iter()
+        .filter(|(_, material)| material.texture.as_ref() == Some(&previous))
+        .map(|(id, _)| id)
+        .collect();
+    for id in font_materials {
+        if let Some(material) = materials.get_mut(&Handle::weak(id)) {
+            material.texture = Some(texture.clone());
+        }
+    }
+    current.filename = font.path.clone();
+    current.font_height_pixels = (font.cell_size.0 as f32, font.cell_size.1 as f32);
+
+    if !matches!(fonts.scaling_mode, TerminalScalingMode::Stretch) {
+        return;
+    }
+    let Some(window) = windows.get_primary_mut() else {
+        return;
+    };
+    window.set_resolution(
+        (width.max(1) as u32 * font.cell_size.0) as f32,
+        (height.max(1) as u32 * font.cell_size.1) as f32,
+    );
+}
@@ -0,0 +1,40 @@
+//! Reports Bevy input to the backend agnostic [`ConsoleInput`].
+
+use bevy::{
+    prelude::{EventReader, Input, KeyCode, MouseButton, Res, ResMut},
+    window::{ReceivedCharacter, WindowCloseRequested, Windows},
+};
+use bracket_bevy::BracketContext;
+use brltk_common::ConsoleInput;
+
+/// Fills the [`ConsoleInput`] resource, with the mouse position in the cells of the active
+/// layer.
+///
+/// The position is converted by `bracket-bevy`, which knows about its
+/// [`TerminalScalingMode`](bracket_bevy::TerminalScalingMode) and gutter. It only reports
+/// whole cells, without the position inside the cell.
+pub(crate) fn update_console_input(
+    ctx: Res<BracketContext>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut close_requested: EventReader<WindowCloseRequested>,
+    mut console_input: ResMut<ConsoleInput>,
+) {
+    let mouse_position = windows
+        .get_primary()
+        .and_then(|window| window.cursor_position())
+        .map(|_| {
+            let cell = ctx.get_mouse_position_for_current_layer();
+            (cell.x as f32, cell.y as f32)
+        });
+
+    console_input.update_from_bevy(
+        &keys,
+        &mouse_buttons,
+        received_characters.iter().map(|event| event.char),
+        mouse_position,
+        close_requested.iter().count() > 0,
+    );
+}
@@ -7,10 +7,15 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+mod console;
+mod font;
+mod input;
+mod palette;
 mod plugin;
+mod resize;
 mod term;
 
 pub use bracket_bevy::{prelude::*, *};
 pub use bracket_pathfinding::prelude::*;
 
-pub use crate::plugin::*;
+pub use crate::{console::*, palette::*, plugin::*};
@@ -0,0 +1,301 @@
+//! Named color palettes, loaded from files and swapped at runtime.
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bevy::prelude::EventReader;
+use bracket_bevy::prelude::{register_palette_color, RGBA};
+
+/// A set of named colors, used by the `#[name]` markup of bracket's printer.
+///
+/// Palettes can be loaded from:
+/// - `.hex` files, with one `RRGGBB` or `RRGGBBAA` color per line,
+/// - `.gpl` GIMP palettes,
+/// - `.json` objects mapping names to `"#RRGGBB"` or `"#RRGGBBAA"` strings.
+///
+/// Colors without a name in the file are named after their index, starting at `"0"`.
+#[derive(Default, Debug, Clone)]
+pub struct ColorPalette {
+    colors: Vec<(String, RGBA)>,
+}
+
+impl ColorPalette {
+    /// Create an empty palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named color to the palette.
+    pub fn with_color<S: ToString, C: Into<RGBA>>(mut self, name: S, color: C) -> Self {
+        self.insert(name, color);
+        self
+    }
+
+    /// Add a named color to the palette, replacing the color with the same name.
+    pub fn insert<S: ToString, C: Into<RGBA>>(&mut self, name: S, color: C) {
+        let name = name.to_string();
+        let color = color.into();
+        match self.colors.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, c)) => *c = color,
+            None => self.colors.push((name, color)),
+        }
+    }
+
+    /// Returns the color with the given name.
+    pub fn get(&self, name: &str) -> Option<RGBA> {
+        self.colors
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, color)| color)
+    }
+
+    /// Returns an iterator over the named colors, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, RGBA)> + '_ {
+        self.colors
+            .iter()
+            .map(|(name, color)| (name.as_str(), *color))
+    }
+
+    /// Returns the number of colors of the palette.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Returns true if the palette has no color.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Load a palette file, picking the format from the file extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PaletteError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let parse = match extension.as_str() {
+            "hex" => Self::from_hex_str,
+            "gpl" => Self::from_gpl_str,
+            "json" => Self::from_json_str,
+            _ => return Err(PaletteError::UnknownFormat(extension)),
+        };
+        parse(&std::fs::read_to_string(path).map_err(PaletteError::Io)?)
+    }
+
+    /// Parse a `.hex` palette, with one `RRGGBB` or `RRGGBBAA` color per line.
+    pub fn from_hex_str(hex: &str) -> Result<Self, PaletteError> {
+        let mut palette = Self::new();
+        for (line, text) in hex.lines().enumerate() {
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let color = parse_hex_color(text).ok_or_else(|| PaletteError::Parse {
+                line: line + 1,
+                message: format!("invalid hex color `{text}`"),
+            })?;
+            palette.insert(palette.len(), color);
+        }
+        Ok(palette)
+    }
+
+    /// Parse a GIMP `.gpl` palette.
+    pub fn from_gpl_str(gpl: &str) -> Result<Self, PaletteError> {
+        let mut palette = Self::new();
+        for (line, text) in gpl.lines().enumerate() {
+            let text = text.trim();
+            if text.is_empty()
+                || text.starts_with('#')
+                || text.starts_with("GIMP Palette")
+                || text.starts_with("Name:")
+                || text.starts_with("Columns:")
+            {
+                continue;
+            }
+
+            let invalid = || PaletteError::Parse {
+                line: line + 1,
+                message: format!("invalid color `{text}`"),
+            };
+            let mut words = text.split_whitespace();
+            let mut channel = || -> Result<u8, PaletteError> {
+                words
+                    .next()
+                    .and_then(|c| c.parse().ok())
+                    .ok_or_else(invalid)
+            };
+            let (r, g, b) = (channel()?, channel()?, channel()?);
+
+            let name = words.collect::<Vec<_>>().join(" ");
+            if name.is_empty() || name == "Untitled" {
+                palette.insert(palette.len(), RGBA::from_u8(r, g, b, 255));
+            } else {
+                palette.insert(name, RGBA::from_u8(r, g, b, 255));
+            }
+        }
+        Ok(palette)
+    }
+
+    /// Parse a `.json` palette, an object mapping names to `"#RRGGBB"` or `"#RRGGBBAA"`
+    /// strings.
+    pub fn from_json_str(json: &str) -> Result<Self, PaletteError> {
+        let colors: BTreeMap<String, String> =
+            serde_json::from_str(json).map_err(PaletteError::Json)?;
+
+        let mut palette = Self::new();
+        for (name, color) in colors {
+            let rgba = parse_hex_color(&color).ok_or_else(|| PaletteError::Parse {
+                line: 0,
+                message: format!("invalid hex color `{color}` for `{name}`"),
+            })?;
+            palette.insert(name, rgba);
+        }
+        Ok(palette)
+    }
+
+    /// Registers every color with bracket's global palette.
+    pub(crate) fn register(&self) {
+        for (name, color) in self.iter() {
+            register_palette_color(name, color);
+        }
+    }
+}
+
+fn parse_hex_color(text: &str) -> Option<RGBA> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(RGBA::from_u8(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+/// An error raised while loading a [`ColorPalette`].
+#[derive(Debug)]
+pub enum PaletteError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file extension is not one of the supported formats.
+    UnknownFormat(String),
+    /// A color of the file is invalid. `line` is 0 for JSON palettes.
+    Parse {
+        /// The line of the invalid color, starting at 1.
+        line: usize,
+        /// What is wrong with the color.
+        message: String,
+    },
+    /// The file is not a valid JSON palette.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "palette i/o error: {e}"),
+            Self::UnknownFormat(extension) => {
+                write!(f, "unknown palette format `{extension}`")
+            }
+            Self::Parse { line: 0, message } => write!(f, "invalid palette: {message}"),
+            Self::Parse { line, message } => {
+                write!(f, "invalid palette at line {line}: {message}")
+            }
+            Self::Json(e) => write!(f, "invalid json palette: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+/// Send this event to register a new [`ColorPalette`] at runtime, for instance to switch to
+/// a colorblind-friendly theme. The colors replace the colors with the same names from the
+/// next frame on; the other named colors are kept.
+#[derive(Debug, Clone)]
+pub struct SetPalette(pub ColorPalette);
+
+/// Registers the palettes sent through [`SetPalette`] events.
+pub(crate) fn apply_palette(mut set_palette_events: EventReader<SetPalette>) {
+    for SetPalette(palette) in set_palette_events.iter() {
+        palette.register();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(palette: &ColorPalette) -> Vec<&str> {
+        palette.iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn hex_palette() {
+        let palette = ColorPalette::from_hex_str("ff0000\n\n  00FF0080\n#0000ff\n").unwrap();
+
+        assert_eq!(names(&palette), ["0", "1", "2"]);
+        assert_eq!(palette.get("0"), Some(RGBA::from_u8(255, 0, 0, 255)));
+        assert_eq!(palette.get("1"), Some(RGBA::from_u8(0, 255, 0, 128)));
+        assert_eq!(palette.get("2"), Some(RGBA::from_u8(0, 0, 255, 255)));
+    }
+
+    #[test]
+    fn invalid_hex_palette() {
+        for hex in ["ff0000\nff00", "ff0000\nggffff", "ff0000\nffé000"] {
+            assert!(matches!(
+                ColorPalette::from_hex_str(hex),
+                Err(PaletteError::Parse { line: 2, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn gpl_palette() {
+        let gpl = "GIMP Palette\n\
+                   Name: Test\n\
+                   Columns: 4\n\
+                   # a comment\n\
+                   255   0   0 Bright Red\n\
+                   \n\
+                     0 128   0\t Untitled\n\
+                     0   0 255\n\
+                   255 255 255 Bright Red\n";
+        let palette = ColorPalette::from_gpl_str(gpl).unwrap();
+
+        assert_eq!(names(&palette), ["Bright Red", "1", "2"]);
+        assert_eq!(
+            palette.get("Bright Red"),
+            Some(RGBA::from_u8(255, 255, 255, 255))
+        );
+        assert_eq!(palette.get("1"), Some(RGBA::from_u8(0, 128, 0, 255)));
+        assert_eq!(palette.get("2"), Some(RGBA::from_u8(0, 0, 255, 255)));
+
+        assert!(matches!(
+            ColorPalette::from_gpl_str("GIMP Palette\n0 0\n"),
+            Err(PaletteError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(
+            ColorPalette::from_gpl_str("GIMP Palette\n0 0 256 Red\n"),
+            Err(PaletteError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn json_palette() {
+        let palette =
+            ColorPalette::from_json_str(r##"{ "wall": "#808080", "water": "0000ff80" }"##).unwrap();
+
+        assert_eq!(names(&palette), ["wall", "water"]);
+        assert_eq!(palette.get("wall"), Some(RGBA::from_u8(128, 128, 128, 255)));
+        assert_eq!(palette.get("water"), Some(RGBA::from_u8(0, 0, 255, 128)));
+
+        assert!(matches!(
+            ColorPalette::from_json_str(r##"{ "wall": "#8080" }"##),
+            Err(PaletteError::Parse { line: 0, .. })
+        ));
+        assert!(matches!(
+            ColorPalette::from_json_str(r##"["#808080"]"##),
+            Err(PaletteError::Json(_))
+        ));
+    }
+}
@@ -1,13 +1,37 @@
-use bevy::utils::{HashMap, HashSet};
+use std::fmt;
+
+use bevy::{
+    input::InputSystem,
+    prelude::{CoreStage, IntoSystemDescriptor},
+    utils::HashSet,
+};
 use bracket_bevy::{prelude::RGBA, *};
-use brltk_common::Backend;
+use brltk_common::{
+    track_window_lifecycle, Backend, BackendCapabilities, ChangeFont, ConsoleInput,
+    ConsoleInputSystem, FontRegistry, ResizePlugin, ResizePolicy,
+};
 
-use crate::term::{
-    default_gutter_size, SimpleConsoleFeatures, SparseConsoleFeatures, TerminalBuilderFont,
-    TerminalLayer,
+use crate::{
+    console::BracketCells,
+    font::BracketFonts,
+    palette::{ColorPalette, SetPalette},
+    resize::BracketConsoles,
+    term::{
+        default_gutter_size, SimpleConsoleFeatures, SparseConsoleFeatures, TerminalBuilderFont,
+        TerminalLayer,
+    },
 };
 
 /// The `bracket-lib` backend.
+///
+/// The terminal is made of simple and sparse console layers, the only console types
+/// supported by `bracket-bevy`. Fancy consoles, with fractional positions and rotation, and
+/// sprite consoles only exist in `bracket-terminal` and are not available with this backend.
+///
+/// The layers are fixed once the backend is built: `bracket-bevy` creates its consoles when
+/// its plugin is added, and does not let them be added, removed, resized, reordered, hidden,
+/// moved or scaled afterwards. Configure every layer that may be needed up front, and clear
+/// a layer to stop drawing on it, for instance to close a modal overlay.
 #[derive(Clone)]
 pub struct BracketLibBackend {
     pub(crate) gutter: f32,
@@ -17,8 +41,8 @@ pub struct BracketLibBackend {
     pub(crate) layers: Vec<TerminalLayer>,
     pub(crate) with_frame_diagnostics: bool,
     pub(crate) fonts: Vec<TerminalBuilderFont>,
-    pub(crate) palette: HashMap<String, RGBA>,
-    pub(crate) scaling_mode: TerminalScalingMode,
+    pub(crate) palette: ColorPalette,
+    pub(crate) resize_policy: ResizePolicy,
     pub(crate) with_random_number_generator: bool,
 }
 
@@ -33,7 +57,7 @@ impl Backend for BracketLibBackend {
         let mut bterm = BTermBuilder::empty()
             .with_gutter(self.gutter)
             .with_timing_log(self.log_diagnostics)
-            .with_scaling_mode(self.scaling_mode)
+            .with_scaling_mode(self.scaling_mode())
             .with_ortho_camera(self.with_ortho_camera)
             .with_auto_apply_batches(self.auto_apply_batches)
             .with_timing_diagnostics(self.with_frame_diagnostics)
@@ -48,6 +72,10 @@ impl Backend for BracketLibBackend {
                 font.font_height_pixels,
             );
         }
+        // Build Palette
+        for (name, color) in self.palette.iter() {
+            bterm = bterm.with_named_color(name, color);
+        }
         // Build Layers
         for layer in &self.layers {
             bterm = match layer {
@@ -78,7 +106,46 @@ impl Backend for BracketLibBackend {
             }
         }
 
-        app.add_plugin(bterm);
+        app.add_plugin(bterm)
+            .add_event::<SetPalette>()
+            .add_system_to_stage(CoreStage::PreUpdate, crate::palette::apply_palette)
+            .init_resource::<BracketCells>()
+            .add_event::<ChangeFont>()
+            .insert_resource(BracketFonts::new(
+                &self.fonts,
+                &self.layers,
+                self.scaling_mode(),
+            ))
+            .add_system_to_stage(CoreStage::PreUpdate, crate::font::handle_change_font)
+            .add_plugin(ResizePlugin::<BracketConsoles>::new(self.resize_policy))
+            .init_resource::<ConsoleInput>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                crate::input::update_console_input
+                    .label(ConsoleInputSystem)
+                    .after(InputSystem),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, track_window_lifecycle);
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            name: "bracket_lib",
+            subcell: false,
+            // bracket-lib only blends colors explicitly, with `RGBA::lerp`.
+            alpha_blending: false,
+            true_color: true,
+            named_colors: true,
+            // Each bracket-lib layer is a console, and sparse consoles only draw the cells
+            // that were set.
+            layers: true,
+            sparse_layers: true,
+            multiple_fonts: true,
+            runtime_font_change: true,
+            runtime_resize: matches!(self.resize_policy, ResizePolicy::KeepCellSize),
+            borders: false,
+            windowed: true,
+        }
     }
 }
 
@@ -90,12 +157,12 @@ impl BracketLibBackend {
             layers: Vec::new(),
             log_diagnostics: false,
             with_ortho_camera: true,
-            palette: HashMap::new(),
+            palette: ColorPalette::new(),
             auto_apply_batches: true,
             with_frame_diagnostics: true,
             gutter: default_gutter_size(),
             with_random_number_generator: false,
-            scaling_mode: TerminalScalingMode::Stretch,
+            resize_policy: ResizePolicy::KeepCellCount,
         }
     }
 
@@ -105,11 +172,11 @@ impl BracketLibBackend {
             log_diagnostics: false,
             with_ortho_camera: true,
             auto_apply_batches: true,
-            palette: HashMap::new(),
+            palette: ColorPalette::new(),
             with_frame_diagnostics: true,
             gutter: default_gutter_size(),
             with_random_number_generator: false,
-            scaling_mode: TerminalScalingMode::Stretch,
+            resize_policy: ResizePolicy::KeepCellCount,
             fonts: vec![TerminalBuilderFont::new(
                 "terminal_8x8.png",
                 16,
@@ -171,12 +238,34 @@ impl BracketLibBackend {
         self
     }
 
-    /// Set [`TerminalScalingMode`] for terminal.
+    /// Set [`TerminalScalingMode`] for terminal, replacing its [`ResizePolicy`]:
+    /// [`TerminalScalingMode::Stretch`] keeps the cell count and
+    /// [`TerminalScalingMode::ResizeTerminals`] keeps the cell size.
     pub fn with_scaling_mode(mut self, scaling_mode: TerminalScalingMode) -> Self {
-        self.scaling_mode = scaling_mode;
+        self.resize_policy = match scaling_mode {
+            TerminalScalingMode::Stretch => ResizePolicy::KeepCellCount,
+            TerminalScalingMode::ResizeTerminals => ResizePolicy::KeepCellSize,
+        };
         self
     }
 
+    /// Set the [`ResizePolicy`] of the terminal, and the [`TerminalScalingMode`] applying
+    /// it: [`ResizePolicy::KeepCellSize`] lets `bracket-bevy` resize the layers, the other
+    /// policies stretch them.
+    ///
+    /// Returns an error for [`ResizePolicy::Letterbox`]: `bracket-bevy` cannot letterbox its
+    /// layers.
+    pub fn with_resize_policy(
+        mut self,
+        resize_policy: ResizePolicy,
+    ) -> Result<Self, UnsupportedResizePolicy> {
+        if let ResizePolicy::Letterbox = resize_policy {
+            return Err(UnsupportedResizePolicy(resize_policy));
+        }
+        self.resize_policy = resize_policy;
+        Ok(self)
+    }
+
     /// Add [`bevy::prelude::Camera2d`] to the terminal.
     pub fn with_ortho_camera(mut self, with_ortho_camera: bool) -> Self {
         self.with_ortho_camera = with_ortho_camera;
@@ -206,9 +295,32 @@ impl BracketLibBackend {
         self
     }
 
-    /// Add color palette to the terminal.
+    /// Add a font of the [`FontRegistry`] to the terminal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no font is registered under this name.
+    pub fn with_registered_font(self, registry: &FontRegistry, name: &str) -> Self {
+        let font = registry.expect(name);
+        self.with_font(
+            &font.path,
+            font.grid.0 as u16,
+            font.grid.1 as u16,
+            (font.cell_size.0 as f32, font.cell_size.1 as f32),
+        )
+    }
+
+    /// Add a named color to the terminal palette, usable with the `#[name]` markup.
     pub fn with_named_color<S: ToString, C: Into<RGBA>>(mut self, name: S, color: C) -> Self {
-        self.palette.insert(name.to_string(), color.into());
+        self.palette.insert(name, color);
+        self
+    }
+
+    /// Add every color of the given [`ColorPalette`] to the terminal palette.
+    pub fn with_palette(mut self, palette: &ColorPalette) -> Self {
+        for (name, color) in palette.iter() {
+            self.palette.insert(name, color);
+        }
         self
     }
 
@@ -235,4 +347,28 @@ impl BracketLibBackend {
         self.gutter = gutter;
         self
     }
+
+    /// The scaling mode applying the resize policy.
+    fn scaling_mode(&self) -> TerminalScalingMode {
+        match self.resize_policy {
+            ResizePolicy::KeepCellSize => TerminalScalingMode::ResizeTerminals,
+            _ => TerminalScalingMode::Stretch,
+        }
+    }
+}
+
+/// A [`ResizePolicy`] the `bracket-lib` backend cannot apply.
+#[derive(Debug, Clone, Copy)]
+pub struct UnsupportedResizePolicy(pub ResizePolicy);
+
+impl fmt::Display for UnsupportedResizePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bracket-bevy does not support the {:?} resize policy",
+            self.0
+        )
+    }
 }
+
+impl std::error::Error for UnsupportedResizePolicy {}
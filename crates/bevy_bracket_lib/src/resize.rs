@@ -0,0 +1,24 @@
+//! Window resize handling.
+
+use bevy::prelude::World;
+use bracket_bevy::BracketContext;
+use brltk_common::ResizeConsoles;
+
+/// Reports the size of the active layer.
+///
+/// The layers are resized by `bracket-bevy` itself, when the window is resized: the backend
+/// sets its [`TerminalScalingMode`](bracket_bevy::TerminalScalingMode) from the
+/// [`ResizePolicy`](brltk_common::ResizePolicy), `ResizeTerminals` for
+/// [`KeepCellSize`](brltk_common::ResizePolicy::KeepCellSize) and `Stretch` otherwise, so
+/// there is nothing left to resize here.
+pub(crate) struct BracketConsoles;
+
+impl ResizeConsoles for BracketConsoles {
+    fn console_size(world: &mut World) -> (u32, u32) {
+        world.resource::<BracketContext>().get_char_size()
+    }
+
+    fn resize_consoles(_world: &mut World, _console_size: (u32, u32)) {
+        // Resized by `bracket-bevy`, see above.
+    }
+}
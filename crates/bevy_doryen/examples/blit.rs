@@ -1,19 +1,9 @@
 use bevy::prelude::*;
 use bevy_doryen::{
-    doryen::{Color, Console as DoryenConsole, TextAlign},
-    DoryenAppOptions, DoryenBackend, RenderSystemExt, RootConsole,
+    doryen::TextAlign, DoryenAppOptions, DoryenBackend, DoryenConsole, RenderSystemExt, RootConsole,
 };
 use brltk_common::Backend;
 
-#[derive(Component)]
-struct Console(DoryenConsole);
-
-#[derive(Default, Copy, Clone, PartialEq, Component)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
 #[derive(Default, Copy, Clone, PartialEq, Component)]
 struct Speed {
     x: i32,
@@ -27,16 +17,11 @@ struct Alpha {
     inverted: bool,
 }
 
-#[derive(Default, Copy, Clone, PartialEq, Component)]
-struct KeyColor(Option<Color>);
-
 #[derive(Bundle)]
 struct ConsoleBundle {
-    console: Console,
-    position: Position,
+    console: DoryenConsole,
     speed: Speed,
     alpha: Alpha,
-    key_color: KeyColor,
 }
 
 #[derive(Default, Copy, Clone, PartialEq, Resource)]
@@ -63,8 +48,11 @@ fn main() {
 }
 
 fn init(mut commands: Commands) {
-    let mut c1 = DoryenConsole::new(20, 20);
-    let mut c2 = DoryenConsole::new(20, 20);
+    let mut c1 = DoryenConsole::new(20, 20).with_position(5, 5);
+    let mut c2 = DoryenConsole::new(20, 20)
+        .with_position(15, 20)
+        .with_z(1)
+        .with_key_color((0, 0, 0, 255));
     for y in 0..20 {
         for x in 0..20 {
             c1.back(x, y, (((x + y * 10) % 255) as u8, 0, 0, 255));
@@ -83,59 +71,62 @@ fn init(mut commands: Commands) {
     c2.print(10, 10, "Circle", TextAlign::Center, None, None);
 
     commands.spawn(ConsoleBundle {
-        console: Console(c1),
-        position: Position { x: 5, y: 5 },
+        console: c1,
         speed: Speed { x: 1, y: 1 },
         alpha: Alpha {
             value: 1.0,
             step: 0.01,
             inverted: false,
         },
-        key_color: KeyColor(None),
     });
 
     commands.spawn(ConsoleBundle {
-        console: Console(c2),
-        position: Position { x: 15, y: 20 },
+        console: c2,
         speed: Speed { x: -1, y: 1 },
         alpha: Alpha {
             value: 1.0,
             step: 0.01,
             inverted: true,
         },
-        key_color: KeyColor(Some((0, 0, 0, 255))),
     });
 }
 
 fn update_position_and_speed(
     step: Res<Step>,
     root_console: Res<RootConsole>,
-    mut position_speed_query: Query<(&mut Position, &mut Speed)>,
+    mut console_query: Query<(&mut DoryenConsole, &mut Speed)>,
 ) {
     if step.0 == 0 {
-        for (mut position, mut speed) in position_speed_query.iter_mut() {
+        for (mut console, mut speed) in console_query.iter_mut() {
             let size = (
                 root_console.get_width() as i32,
                 root_console.get_height() as i32,
             );
-            position.x += speed.x;
-            if position.x == size.0 - 20 || position.x == 0 {
+            let position = &mut console.position;
+            position.0 += speed.x;
+            if position.0 == size.0 - 20 || position.0 == 0 {
                 speed.x = -speed.x;
             }
-            position.y += speed.y;
-            if position.y == size.1 - 20 || position.y == 0 {
+            position.1 += speed.y;
+            if position.1 == size.1 - 20 || position.1 == 0 {
                 speed.y = -speed.y;
             }
         }
     }
 }
 
-fn update_alpha(mut console_query: Query<(&mut Alpha, &Console)>) {
-    for (mut alpha, _) in console_query.iter_mut() {
+fn update_alpha(mut console_query: Query<(&mut Alpha, &mut DoryenConsole)>) {
+    for (mut alpha, mut console) in console_query.iter_mut() {
         if alpha.value <= 0.0 || alpha.value >= 1.0 {
             alpha.step = -alpha.step;
         }
         alpha.value += alpha.step;
+
+        console.set_alpha(if alpha.inverted {
+            1.0 - alpha.value
+        } else {
+            alpha.value
+        });
     }
 }
 
@@ -143,10 +134,8 @@ fn update_step(mut step: ResMut<Step>) {
     step.0 = (step.0 + 1) % 10;
 }
 
-fn render(
-    mut root_console: ResMut<RootConsole>,
-    console_query: Query<(&Position, &Alpha, &KeyColor, &Console)>,
-) {
+// The offscreen consoles are blitted on top of the root console after this system runs.
+fn render(mut root_console: ResMut<RootConsole>) {
     let root_console = &mut **root_console;
     root_console.clear(Some((0, 0, 0, 255)), None, Some(' ' as u16));
     for x in 0..root_console.get_width() as i32 {
@@ -170,20 +159,4 @@ fn render(
         Some((255, 255, 255, 255)),
         None,
     );
-
-    for (position, alpha, key_color, console) in console_query.iter() {
-        let alpha = if alpha.inverted {
-            1.0 - alpha.value
-        } else {
-            alpha.value
-        };
-        console.0.blit(
-            position.x,
-            position.y,
-            root_console,
-            alpha,
-            alpha,
-            key_color.0,
-        );
-    }
 }
@@ -3,36 +3,14 @@ use bevy_doryen::{
     doryen::TextAlign, DoryenAppOptions, DoryenBackend, DoryenInput, RenderSystemExt, RootConsole,
     SetFontPath,
 };
-use brltk_common::Backend;
+use brltk_common::{Backend, Charset, FontRegistry};
 const CONSOLE_WIDTH: u32 = 40;
 const CONSOLE_HEIGHT: u32 = 25;
 
-const FONTS: [&str; 19] = [
-    "terminal_8x8.png",
-    "terminal_colored_8x8.png",
-    "terminal_8x12.png",
-    "terminal_10x16.png",
-    "terminal_12x12.png",
-    "SmoothWalls_9x9.png",
-    "Aesomatica_16x16.png",
-    "Bisasam_20x20.png",
-    "Buddy--graphical_10x10.png",
-    "Cheepicus_8x8.png",
-    "Cheepicus_15x15.png",
-    "Cheepicus_16x16.png",
-    "Herrbdog_12x12.png",
-    "Kein_5x5.png",
-    "Mkv_curses_6x6.png",
-    "Runeset_24x24.png",
-    "Teeto_K_18x18.png",
-    "Terbert_7x7.png",
-    "Yayo_tunur_13x13.png",
-];
-
 #[derive(Default, Resource)]
 struct Font {
     current_font: usize,
-    current_font_name: &'static str,
+    current_font_name: String,
 }
 
 fn main() {
@@ -51,29 +29,41 @@ fn main() {
     }
     .build(&mut app);
 
-    app.init_resource::<Font>()
+    app.insert_resource(doryen_fonts())
+        .init_resource::<Font>()
         .add_system(update)
         .add_doryen_render_system(render)
         .run();
 }
 
+/// The bundled fonts Doryen is able to draw: 16 by 16 glyphs in the code page 437 order.
+fn doryen_fonts() -> FontRegistry {
+    FontRegistry::from_ron(include_str!("../../../assets/fonts.ron"))
+        .expect("the font manifest is valid")
+        .iter()
+        .filter(|font| font.grid == (16, 16) && font.charset == Charset::Cp437)
+        .cloned()
+        .fold(FontRegistry::new(), FontRegistry::register)
+}
+
 fn update(
     mut font: ResMut<Font>,
+    fonts: Res<FontRegistry>,
     input: Res<DoryenInput>,
     mut set_font_path: EventWriter<SetFontPath>,
 ) {
-    let mut font_path = None;
+    let mut font_info = None;
     if input.key_released(doryen_rs::ScanCode::A) {
-        font.current_font = (font.current_font + 1) % FONTS.len();
-        font_path = Some(FONTS[font.current_font]);
+        font.current_font = (font.current_font + 1) % fonts.len();
+        font_info = fonts.iter().nth(font.current_font);
     } else if input.key_released(doryen_rs::ScanCode::D) {
-        font.current_font = (font.current_font + FONTS.len() - 1) % FONTS.len();
-        font_path = Some(FONTS[font.current_font]);
+        font.current_font = (font.current_font + fonts.len() - 1) % fonts.len();
+        font_info = fonts.iter().nth(font.current_font);
     }
 
-    if let Some(font_path) = font_path {
-        font.current_font_name = font_path;
-        set_font_path.send(SetFontPath(String::from(font_path).into()));
+    if let Some(font_info) = font_info {
+        font.current_font_name = font_info.name.clone();
+        set_font_path.send(SetFontPath(font_info.path.clone().into()));
     }
 }
 
@@ -118,7 +108,7 @@ fn render(mut root_console: ResMut<RootConsole>, font: Res<Font>) {
     root_console.print(
         (CONSOLE_WIDTH / 2) as i32,
         (CONSOLE_HEIGHT / 2) as i32,
-        font.current_font_name,
+        &font.current_font_name,
         TextAlign::Center,
         Some((255, 255, 255, 255)),
         None,
@@ -0,0 +1,227 @@
+//! Loading of the Doryen fonts and images through the Bevy [`AssetServer`].
+
+use std::borrow::Cow;
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+use bevy::asset::FileAssetIo;
+#[cfg(feature = "hot_reload")]
+use bevy::{
+    asset::AssetEvent,
+    prelude::{CoreStage, EventReader, EventWriter, Res},
+};
+use bevy::{
+    asset::{
+        AddAsset, AssetLoader, AssetPlugin, AssetServer, Assets, Handle, LoadContext, LoadState,
+        LoadedAsset,
+    },
+    log::warn,
+    prelude::{App, Plugin, Resource, World},
+    tasks::{IoTaskPool, TaskPool},
+    utils::BoxedFuture,
+};
+
+use crate::DoryenImage;
+#[cfg(feature = "hot_reload")]
+use crate::SetFontPath;
+
+/// Asset plugin for Bevy Doryen.
+///
+/// Adds the [`AssetPlugin`] if the app does not have an [`AssetServer`] yet. To load the
+/// assets from another folder, add the [`AssetPlugin`] with its `asset_folder` before the
+/// Doryen backend.
+///
+/// With the `hot_reload` feature, the added [`AssetPlugin`] watches the asset folder: the
+/// modified [`DoryenImage`]s are reloaded, and the [`DoryenFont`] is handed to Doryen again
+/// when its file is modified.
+pub struct DoryenAssetPlugin;
+impl Plugin for DoryenAssetPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<AssetServer>() {
+            // The assets are loaded on the IO task pool, set up by the `CorePlugin` otherwise.
+            IoTaskPool::init(TaskPool::default);
+            app.add_plugin(AssetPlugin {
+                watch_for_changes: cfg!(feature = "hot_reload"),
+                ..Default::default()
+            });
+        }
+
+        app.add_asset::<DoryenImage>()
+            .add_asset_loader(DoryenImageLoader);
+
+        #[cfg(feature = "hot_reload")]
+        app.add_system_to_stage(CoreStage::Last, reload_modified_font);
+    }
+}
+
+/// Loads the PNG files as [`DoryenImage`]s.
+#[derive(Debug, Default)]
+pub struct DoryenImageLoader;
+
+impl AssetLoader for DoryenImageLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let rgba = image::load_from_memory(bytes)?.to_rgba8();
+            load_context.set_default_asset(LoadedAsset::new(DoryenImage::from_rgba(&rgba)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+}
+
+/// The font used by Doryen, also loaded as a [`DoryenImage`] asset.
+///
+/// Doryen reads the font file itself: a font sent with [`SetFontPath`](crate::SetFontPath)
+/// is only handed to Doryen once its asset is loaded. A font failing to load is ignored,
+/// and the current font is kept.
+#[derive(Debug, Clone, Resource)]
+pub struct DoryenFont {
+    path: Cow<'static, str>,
+    handle: Handle<DoryenImage>,
+    pending: Option<(Cow<'static, str>, Handle<DoryenImage>)>,
+}
+
+impl DoryenFont {
+    pub(crate) fn new(world: &World, path: Cow<'static, str>) -> Self {
+        Self {
+            handle: world.resource::<AssetServer>().load(path.as_ref()),
+            path,
+            pending: None,
+        }
+    }
+
+    /// Returns the path of the current font, relative to the asset folder.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the handle of the current font.
+    pub fn handle(&self) -> &Handle<DoryenImage> {
+        &self.handle
+    }
+
+    /// Returns the path of the font being loaded, if any.
+    pub fn pending_path(&self) -> Option<&str> {
+        self.pending.as_ref().map(|(path, _)| path.as_ref())
+    }
+}
+
+/// Starts loading a font requested by a [`SetFontPath`](crate::SetFontPath) event.
+pub(crate) fn request_font(world: &mut World, path: Cow<'static, str>) {
+    let handle = world.resource::<AssetServer>().load(path.as_ref());
+    world.resource_mut::<DoryenFont>().pending = Some((path, handle));
+}
+
+/// Returns the full path of the requested font once it is loaded, to hand it to Doryen.
+pub(crate) fn take_loaded_font(world: &mut World) -> Option<String> {
+    let font = world.resource::<DoryenFont>();
+    let (path, handle) = font.pending.as_ref()?;
+    match world.resource::<AssetServer>().get_load_state(handle) {
+        LoadState::Loaded => {
+            let full_path = asset_path(world, path);
+            let mut font = world.resource_mut::<DoryenFont>();
+            let (path, handle) = font.pending.take()?;
+            font.path = path;
+            font.handle = handle;
+            Some(full_path)
+        }
+        LoadState::Failed => {
+            warn!("Failed to load the font {path}, keeping {}", font.path);
+            world.resource_mut::<DoryenFont>().pending = None;
+            None
+        }
+        LoadState::NotLoaded | LoadState::Loading | LoadState::Unloaded => None,
+    }
+}
+
+/// Returns the size in pixels of a cell of the current font, once its image is loaded.
+/// Doryen fonts have 16 by 16 glyphs.
+pub(crate) fn font_cell_size(world: &World) -> Option<(u32, u32)> {
+    let font = world.resource::<DoryenFont>();
+    let image = world.resource::<Assets<DoryenImage>>().get(&font.handle)?;
+    Some((image.width() / 16, image.height() / 16))
+}
+
+/// Returns the console size keeping the zoom of the cells when the font changes from
+/// cells of `previous_cell_size` to cells of `cell_size` pixels, for the same window.
+pub(crate) fn console_size_for_font(
+    console_size: (u32, u32),
+    previous_cell_size: (u32, u32),
+    cell_size: (u32, u32),
+) -> (u32, u32) {
+    (
+        (console_size.0 * previous_cell_size.0 / cell_size.0.max(1)).max(1),
+        (console_size.1 * previous_cell_size.1 / cell_size.1.max(1)).max(1),
+    )
+}
+
+/// Sends a [`SetFontPath`](crate::SetFontPath) event when the file of the current
+/// [`DoryenFont`] is modified, to hand it to Doryen again.
+#[cfg(feature = "hot_reload")]
+fn reload_modified_font(
+    mut asset_events: EventReader<AssetEvent<DoryenImage>>,
+    font: Res<DoryenFont>,
+    mut set_font_path: EventWriter<SetFontPath>,
+) {
+    for event in asset_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if *handle == font.handle {
+                set_font_path.send(SetFontPath(font.path.clone()));
+            }
+        }
+    }
+}
+
+/// Returns the path of an asset for Doryen, which reads the font files itself: in the asset
+/// folder of the [`AssetServer`] on desktop, in the `assets` folder on the other platforms.
+pub(crate) fn asset_path(world: &World, path: &str) -> String {
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    if let Some(file_asset_io) = world
+        .resource::<AssetServer>()
+        .asset_io()
+        .downcast_ref::<FileAssetIo>()
+    {
+        return file_asset_io
+            .root_path()
+            .join(path)
+            .to_string_lossy()
+            .into_owned();
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+    let _ = world;
+    format!("assets/{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_cell_size() {
+        assert_eq!(console_size_for_font((80, 50), (8, 8), (8, 8)), (80, 50));
+    }
+
+    #[test]
+    fn larger_cells() {
+        assert_eq!(console_size_for_font((80, 50), (8, 8), (8, 16)), (80, 25));
+        assert_eq!(console_size_for_font((80, 50), (8, 8), (16, 16)), (40, 25));
+    }
+
+    #[test]
+    fn smaller_cells() {
+        assert_eq!(console_size_for_font((40, 25), (16, 16), (8, 8)), (80, 50));
+    }
+
+    #[test]
+    fn keeps_one_cell() {
+        assert_eq!(console_size_for_font((1, 1), (8, 8), (32, 32)), (1, 1));
+        assert_eq!(console_size_for_font((80, 50), (8, 8), (0, 0)), (640, 400));
+    }
+}
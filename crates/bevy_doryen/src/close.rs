@@ -0,0 +1,295 @@
+//! Handling of the requests to close the window.
+
+use bevy::{
+    app::AppExit,
+    prelude::{Events, Res, ResMut, Resource, World},
+};
+use brltk_common::AppLifecycle;
+use doryen_rs::{Color, ScanCode, TextAlign};
+
+use crate::{
+    CellRect, CloseRequestPolicy, DirtyRegions, DoryenInput, RootConsole, WindowCloseRequested,
+};
+
+const DIALOG_FORE: Color = (255, 255, 255, 255);
+const DIALOG_BACK: Color = (32, 32, 48, 255);
+
+/// The confirmation dialog shown by [`CloseRequestPolicy::Confirm`].
+///
+/// The dialog is drawn on top of the [`RootConsole`] after the
+/// [`DoryenRenderStage::Render`](crate::DoryenRenderStage::Render) systems. `Y` or `Enter`
+/// exits the app, `N` or `Escape` closes the dialog. The dialog can also be opened by the
+/// game, for instance when `Escape` is pressed.
+///
+/// The dialog is modal: while it is open, the game systems see no keyboard input, not even
+/// the keys answering it, and the keys held down are released.
+///
+/// The dialog is closed when the app sends [`AppLifecycle::Exiting`], so the last frame is
+/// drawn without it.
+#[derive(Debug, Clone, Resource)]
+pub struct CloseConfirmation {
+    open: bool,
+    message: String,
+}
+
+impl Default for CloseConfirmation {
+    fn default() -> Self {
+        Self {
+            open: false,
+            message: String::from("Exit the game? (Y/N)"),
+        }
+    }
+}
+
+impl CloseConfirmation {
+    /// Returns true if the dialog is shown.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show the dialog.
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Hide the dialog.
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Set the question asked by the dialog.
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = message.into();
+    }
+}
+
+/// Sends the [`WindowCloseRequested`] event and applies the [`CloseRequestPolicy`], before the
+/// app is updated and before the [`ConsoleInput`](brltk_common::ConsoleInput) is filled.
+pub(crate) fn handle_close_request(world: &mut World, policy: CloseRequestPolicy) {
+    let input = world.resource::<DoryenInput>();
+    let close_requested = input.close_requested();
+    let confirmed = input.key_pressed(ScanCode::Y) || input.key_pressed(ScanCode::Enter);
+    let cancelled = input.key_pressed(ScanCode::N) || input.key_pressed(ScanCode::Escape);
+
+    if close_requested {
+        world
+            .resource_mut::<Events<WindowCloseRequested>>()
+            .send(WindowCloseRequested);
+    }
+
+    let exit = match policy {
+        CloseRequestPolicy::Exit => close_requested,
+        CloseRequestPolicy::Event => false,
+        CloseRequestPolicy::Confirm => {
+            let mut confirmation = world.resource_mut::<CloseConfirmation>();
+            let was_open = confirmation.is_open();
+            let exit = if close_requested {
+                confirmation.open();
+                false
+            } else if was_open && cancelled {
+                confirmation.close();
+                false
+            } else {
+                was_open && confirmed
+            };
+            // The dialog takes the keyboard, so the key closing it does not reach the game.
+            if was_open || close_requested {
+                world.resource_mut::<DoryenInput>().consume_keys();
+            }
+            exit
+        }
+    };
+    if exit {
+        world.resource_mut::<Events<AppExit>>().send(AppExit);
+    }
+}
+
+/// Closes the [`CloseConfirmation`] dialog once the app is exiting, so the last frame is
+/// drawn without it, whichever answer or system triggered the exit.
+pub(crate) fn handle_lifecycle(world: &mut World, event: AppLifecycle) {
+    if event != AppLifecycle::Exiting {
+        return;
+    }
+    if let Some(mut confirmation) = world.get_resource_mut::<CloseConfirmation>() {
+        confirmation.close();
+    }
+}
+
+/// Draws the [`CloseConfirmation`] dialog when it is open, and marks the cells it covers to
+/// be redrawn once it is closed.
+pub(crate) fn render_close_confirmation(
+    confirmation: Res<CloseConfirmation>,
+    mut root_console: ResMut<RootConsole>,
+    mut dirty_regions: ResMut<DirtyRegions>,
+) {
+    if !confirmation.is_open() {
+        return;
+    }
+
+    let (console_width, console_height) = root_console.get_size();
+    let width = (confirmation.message.chars().count() as u32 + 4).min(console_width);
+    let height = 5.min(console_height);
+    let x = (console_width - width) as i32 / 2;
+    let y = (console_height - height) as i32 / 2;
+
+    root_console.rectangle(
+        x,
+        y,
+        width,
+        height,
+        Some(DIALOG_FORE),
+        Some(DIALOG_BACK),
+        Some(' ' as u16),
+    );
+    root_console.print(
+        (console_width / 2) as i32,
+        y + height as i32 / 2,
+        &confirmation.message,
+        TextAlign::Center,
+        Some(DIALOG_FORE),
+        Some(DIALOG_BACK),
+    );
+    dirty_regions.mark_composited(CellRect::new(x, y, width, height));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use bevy::prelude::{Mut, Schedule, Stage, SystemStage};
+    use doryen_rs::Console;
+
+    use crate::InputFrame;
+
+    use super::*;
+
+    fn app() -> World {
+        let mut world = World::new();
+        world.init_resource::<DoryenInput>();
+        world.init_resource::<CloseConfirmation>();
+        world.init_resource::<Events<WindowCloseRequested>>();
+        world.init_resource::<Events<AppExit>>();
+        world
+    }
+
+    fn frame(world: &mut World, frame: InputFrame) {
+        world
+            .resource_mut::<DoryenInput>()
+            .handle_input(&frame, None, Instant::now());
+        handle_close_request(world, CloseRequestPolicy::Confirm);
+    }
+
+    fn pressed(scan_code: ScanCode) -> InputFrame {
+        InputFrame {
+            keys_pressed: vec![scan_code],
+            text: String::from("y"),
+            ..Default::default()
+        }
+    }
+
+    fn close_requested() -> InputFrame {
+        InputFrame {
+            close_requested: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keys_pass_through_while_closed() {
+        let mut world = app();
+        frame(&mut world, pressed(ScanCode::Escape));
+        let input = world.resource::<DoryenInput>();
+        assert!(input.key_pressed(ScanCode::Escape));
+        assert_eq!(input.text(), "y");
+        assert!(!world.resource::<CloseConfirmation>().is_open());
+    }
+
+    #[test]
+    fn cancelling_consumes_the_key() {
+        let mut world = app();
+        frame(&mut world, close_requested());
+        assert!(world.resource::<CloseConfirmation>().is_open());
+        assert_eq!(world.resource::<Events<WindowCloseRequested>>().len(), 1);
+
+        frame(&mut world, pressed(ScanCode::Escape));
+        assert!(!world.resource::<CloseConfirmation>().is_open());
+        let input = world.resource::<DoryenInput>();
+        assert!(!input.key_pressed(ScanCode::Escape));
+        assert!(!input.key(ScanCode::Escape));
+        assert_eq!(input.text(), "");
+        assert!(world.resource::<Events<AppExit>>().is_empty());
+    }
+
+    #[test]
+    fn confirming_exits() {
+        let mut world = app();
+        world.resource_mut::<CloseConfirmation>().open();
+        frame(&mut world, pressed(ScanCode::Y));
+        assert!(!world.resource::<DoryenInput>().key_pressed(ScanCode::Y));
+        assert_eq!(world.resource::<Events<AppExit>>().len(), 1);
+    }
+
+    #[test]
+    fn exiting_closes_the_dialog() {
+        let mut world = app();
+        frame(&mut world, close_requested());
+        handle_lifecycle(&mut world, AppLifecycle::FocusLost);
+        assert!(world.resource::<CloseConfirmation>().is_open());
+
+        frame(&mut world, pressed(ScanCode::Y));
+        assert_eq!(world.resource::<Events<AppExit>>().len(), 1);
+        handle_lifecycle(&mut world, AppLifecycle::Exiting);
+        assert!(!world.resource::<CloseConfirmation>().is_open());
+    }
+
+    #[test]
+    fn opening_releases_the_held_keys() {
+        let mut world = app();
+        frame(&mut world, pressed(ScanCode::Left));
+        assert!(world.resource::<DoryenInput>().key(ScanCode::Left));
+        frame(&mut world, close_requested());
+        assert!(!world.resource::<DoryenInput>().key(ScanCode::Left));
+    }
+
+    fn render(world: &mut World) {
+        world.resource_scope(|world, mut dirty_regions: Mut<DirtyRegions>| {
+            dirty_regions.clear_composited(&mut world.resource_mut::<RootConsole>());
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "last",
+            SystemStage::single_threaded().with_system(render_close_confirmation),
+        );
+        schedule.run(world);
+        world.resource_scope(|world, mut dirty_regions: Mut<DirtyRegions>| {
+            dirty_regions.end_render(world.resource::<RootConsole>());
+        });
+    }
+
+    #[test]
+    fn closing_redraws_beneath_the_dialog() {
+        let mut world = app();
+        world.insert_resource(RootConsole(Some(Console::new(40, 11))));
+        world.init_resource::<DirtyRegions>();
+        render(&mut world);
+        assert!(!world.resource::<DirtyRegions>().is_any_dirty());
+
+        frame(&mut world, close_requested());
+        render(&mut world);
+        let (x, y) = (8, 3);
+        assert_eq!(
+            world.resource::<RootConsole>().get_back(x, y),
+            Some(&DIALOG_BACK)
+        );
+
+        frame(&mut world, pressed(ScanCode::N));
+        world.resource_scope(|world, mut dirty_regions: Mut<DirtyRegions>| {
+            dirty_regions.clear_composited(&mut world.resource_mut::<RootConsole>());
+        });
+        let dirty_regions = world.resource::<DirtyRegions>();
+        assert!(dirty_regions.is_dirty(&CellRect::new(x, y, 24, 5)));
+        assert!(dirty_regions.is_cell_dirty(31, 7));
+        assert!(!dirty_regions.is_cell_dirty(7, 3));
+        assert!(!dirty_regions.is_cell_dirty(20, 8));
+    }
+}
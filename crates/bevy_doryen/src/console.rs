@@ -0,0 +1,62 @@
+//! [`ConsoleApi`] implementation for `doryen-rs` consoles.
+
+use brltk_common::{ConsoleApi, Rgba, TextAlign};
+use doryen_rs::{Console, TextAlign as DoryenTextAlign};
+
+#[inline]
+fn to_ascii(glyph: char) -> Option<u16> {
+    u16::try_from(glyph as u32).ok()
+}
+
+/// Use `&mut **root_console` to draw on the [`RootConsole`](crate::RootConsole) through
+/// this implementation.
+impl ConsoleApi for Console {
+    fn size(&self) -> (u32, u32) {
+        self.get_size()
+    }
+
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        self.cell(x, y, glyph.and_then(to_ascii), fore, back);
+    }
+
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char> {
+        self.get_ascii(x, y)
+            .and_then(|ascii| char::from_u32(ascii as u32))
+    }
+
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba> {
+        Console::get_fore(self, x, y).copied()
+    }
+
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba> {
+        Console::get_back(self, x, y).copied()
+    }
+
+    fn clear(&mut self, fore: Option<Rgba>, back: Option<Rgba>, glyph: Option<char>) {
+        Console::clear(self, fore, back, glyph.and_then(to_ascii));
+    }
+
+    fn print(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        align: TextAlign,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        let align = match align {
+            TextAlign::Left => DoryenTextAlign::Left,
+            TextAlign::Right => DoryenTextAlign::Right,
+            TextAlign::Center => DoryenTextAlign::Center,
+        };
+        Console::print(self, x, y, text, align, fore, back);
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::{
     app::AppExit,
     ecs::event::ManualEventReader,
-    prelude::{Events, Schedule, Stage},
+    prelude::{Events, Mut, Schedule, Stage},
+    time::TimeUpdateStrategy,
 };
+use brltk_common::{ConsoleInput, ConsoleResized, Repeat};
 use doryen_rs::{Console, DoryenApi, Engine, MouseButton, UpdateEvent};
 
 use crate::*;
@@ -15,6 +17,9 @@ pub struct DoryenEngine {
     pub(crate) previous_screen_size: (u32, u32),
     pub(crate) previous_console_size: (u32, u32),
     pub(crate) mouse_button_listeners: Vec<MouseButton>,
+    pub(crate) key_repeat: Option<Repeat>,
+    pub(crate) time_pinned: bool,
+    pub(crate) close_request_policy: CloseRequestPolicy,
     pub(crate) app_exit_event_reader: ManualEventReader<AppExit>,
     pub(crate) set_font_path_event_reader: ManualEventReader<SetFontPath>,
 }
@@ -75,12 +80,84 @@ impl DoryenEngine {
     #[inline]
     fn handle_input(&mut self, api: &mut dyn DoryenApi) {
         let input = api.input();
+        let mut input_recorder = self
+            .bevy_app
+            .world
+            .get_resource_mut::<InputRecorder>()
+            .unwrap();
+        let replay_started = input_recorder.take_replay_started();
+        let (frame, now) = input_recorder.next_frame(&self.mouse_button_listeners, input);
+        let pin_time = input_recorder.is_recording() || input_recorder.is_replaying();
+
+        // Bevy's time follows the recorded frames, so the replayed systems see the same
+        // deltas as the recorded ones.
+        if let Some(mut time_update_strategy) =
+            self.bevy_app.world.get_resource_mut::<TimeUpdateStrategy>()
+        {
+            if pin_time {
+                *time_update_strategy = TimeUpdateStrategy::ManualInstant(now);
+            } else if self.time_pinned {
+                *time_update_strategy = TimeUpdateStrategy::Automatic;
+            }
+        }
+        self.time_pinned = pin_time;
+
         let mut doryen_input = self
             .bevy_app
             .world
             .get_resource_mut::<DoryenInput>()
             .unwrap();
-        doryen_input.handle_input(&self.mouse_button_listeners, input);
+        if replay_started {
+            // Replays start from a blank input state, like the recording did.
+            *doryen_input = DoryenInput::default();
+        }
+        doryen_input.handle_input(&frame, self.key_repeat, now);
+
+        // The close confirmation dialog takes the keyboard before the game sees it.
+        crate::close::handle_close_request(&mut self.bevy_app.world, self.close_request_policy);
+
+        self.bevy_app
+            .world
+            .resource_scope(|world, mut doryen_input: Mut<DoryenInput>| {
+                doryen_input.sync_console_input(&mut world.resource_mut::<ConsoleInput>());
+                doryen_input.send_mouse_events(world);
+            });
+    }
+
+    /// Keeps the cells at the same zoom when the font changes, under
+    /// [`ResizeMode::Automatic`]. Doryen cannot resize its window, so the console is
+    /// resized to fill it with the cells of the new font.
+    fn change_cell_size(
+        &mut self,
+        api: &mut dyn DoryenApi,
+        previous_cell_size: (u32, u32),
+        cell_size: (u32, u32),
+    ) {
+        if previous_cell_size == cell_size || !matches!(self.resize_mode, ResizeMode::Automatic) {
+            return;
+        }
+
+        let console_size = crate::assets::console_size_for_font(
+            self.previous_console_size,
+            previous_cell_size,
+            cell_size,
+        );
+        api.con().resize(console_size.0, console_size.1);
+        self.bevy_app
+            .world
+            .resource_mut::<DirtyRegions>()
+            .mark_all_dirty();
+        self.bevy_app
+            .world
+            .resource_mut::<Events<ConsoleResized>>()
+            .send(ConsoleResized {
+                previous_window_size: self.previous_screen_size,
+                window_size: self.previous_screen_size,
+                previous_console_size: self.previous_console_size,
+                console_size,
+            });
+
+        self.previous_console_size = console_size;
     }
 }
 
@@ -100,18 +177,29 @@ impl Engine for DoryenEngine {
         self.bevy_app.update();
         self.restore_root_console_ownership(api);
 
-        // Process the latest SetFontPath event
+        // Load the font of the latest SetFontPath event, and hand it to Doryen once loaded
         let doryen_set_font_path_events = self
             .bevy_app
             .world
-            .get_resource_mut::<Events<SetFontPath>>()
+            .get_resource::<Events<SetFontPath>>()
             .unwrap();
-        if let Some(doryen_set_font_path) = self
+        let font_path = self
             .set_font_path_event_reader
-            .iter(&doryen_set_font_path_events)
+            .iter(doryen_set_font_path_events)
             .last()
-        {
-            api.set_font_path(&format!("assets/{}", doryen_set_font_path.0.as_ref()));
+            .map(|doryen_set_font_path| doryen_set_font_path.0.clone());
+        if let Some(font_path) = font_path {
+            crate::assets::request_font(&mut self.bevy_app.world, font_path);
+        }
+        let previous_cell_size = crate::assets::font_cell_size(&self.bevy_app.world);
+        if let Some(font_path) = crate::assets::take_loaded_font(&mut self.bevy_app.world) {
+            api.set_font_path(&font_path);
+            if let (Some(previous_cell_size), Some(cell_size)) = (
+                previous_cell_size,
+                crate::assets::font_cell_size(&self.bevy_app.world),
+            ) {
+                self.change_cell_size(api, previous_cell_size, cell_size);
+            }
         }
 
         if let Some(app_exit_events) = self.bevy_app.world.get_resource_mut::<Events<AppExit>>() {
@@ -146,6 +234,12 @@ impl Engine for DoryenEngine {
         doryen_render_schedule.run(&mut self.bevy_app.world);
         self.restore_doryen_render_schedule(doryen_render_schedule);
 
+        self.bevy_app
+            .world
+            .resource_scope(|world, mut dirty_regions: Mut<DirtyRegions>| {
+                dirty_regions.end_render(world.resource::<RootConsole>());
+            });
+
         self.restore_root_console_ownership(api);
     }
 
@@ -165,6 +259,10 @@ impl Engine for DoryenEngine {
             new_height,
         };
         resized_events.send(resized);
+        self.bevy_app
+            .world
+            .resource_mut::<DirtyRegions>()
+            .mark_all_dirty();
 
         match self.resize_mode {
             ResizeMode::Nothing => (),
@@ -188,7 +286,18 @@ impl Engine for DoryenEngine {
             }
         }
 
+        let console_size = api.con().get_size();
+        self.bevy_app
+            .world
+            .resource_mut::<Events<ConsoleResized>>()
+            .send(ConsoleResized {
+                previous_window_size: self.previous_screen_size,
+                window_size: (new_width, new_height),
+                previous_console_size: self.previous_console_size,
+                console_size,
+            });
+
         self.previous_screen_size = (new_width, new_height);
-        self.previous_console_size = api.con().get_size();
+        self.previous_console_size = console_size;
     }
 }
@@ -2,24 +2,72 @@
 
 use std::borrow::Cow;
 
-use bevy::prelude::{App, Plugin};
+use bevy::{
+    log::warn,
+    prelude::{App, CoreStage, EventReader, EventWriter, Plugin},
+};
+use brltk_common::{ChangeFont, Charset, ConsoleResized, FontInfo};
+use doryen_rs::MouseButton;
 
 /// Event plugin for handling `doryten-rs` events.
 pub struct DoryenEventPlugin;
 impl Plugin for DoryenEventPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SetFontPath>().add_event::<Resized>();
+        app.add_event::<SetFontPath>()
+            .add_event::<ChangeFont>()
+            .add_event::<Resized>()
+            .add_event::<ConsoleResized>()
+            .add_event::<WindowCloseRequested>()
+            .add_event::<MouseDrag>()
+            .add_event::<MouseDoubleClick>()
+            .add_event::<MouseCellEntered>()
+            .add_system_to_stage(CoreStage::Last, forward_change_font);
     }
 }
 
+/// Sends a [`SetFontPath`] event for the latest [`ChangeFont`] event Doryen can draw.
+/// Doryen reads the cell size from the font image, so only the path is used, and only
+/// draws fonts of 16x16 glyphs in the code page 437 order: the other fonts are ignored.
+fn forward_change_font(
+    mut change_font: EventReader<ChangeFont>,
+    mut set_font_path: EventWriter<SetFontPath>,
+) {
+    let font = change_font
+        .iter()
+        .map(|ChangeFont(font)| font)
+        .rev()
+        .find(|font| is_doryen_font(font));
+    if let Some(font) = font {
+        set_font_path.send(SetFontPath(font.path.clone().into()));
+    }
+}
+
+fn is_doryen_font(font: &FontInfo) -> bool {
+    let supported = font.grid == (16, 16) && font.charset == Charset::Cp437;
+    if !supported {
+        warn!(
+            "Doryen cannot draw the font {}: it needs 16x16 glyphs in the code page 437 order",
+            font.name
+        );
+    }
+    supported
+}
+
 /// When you want to change Doryen's font path, emit an event of this type.
-/// `bevy_doryen` will call [`set_font_path`](doryen_rs::DoryenApi) with the
-/// provided value.
+/// The path is relative to the asset folder. `bevy_doryen` loads the font as a
+/// [`DoryenFont`](crate::DoryenFont) asset, then calls
+/// [`set_font_path`](doryen_rs::DoryenApi) once it is loaded.
 #[derive(Debug, Clone)]
 pub struct SetFontPath(pub Cow<'static, str>);
 
+/// Sent when the user asks to close the window, before the
+/// [`CloseRequestPolicy`](crate::CloseRequestPolicy) is applied.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowCloseRequested;
+
 /// Resized event object. Whenever Doryen's [`resize`](doryen_rs::Engine::resize) method is
-/// called, an event of this type is emitted.
+/// called, an event of this type is emitted, followed by a [`ConsoleResized`] event once the
+/// [`ResizeMode`](crate::ResizeMode) was applied.
 #[derive(Debug, Clone, Copy)]
 pub struct Resized {
     /// The previous width of the Doryen game window.
@@ -31,3 +79,110 @@ pub struct Resized {
     /// The new height of the Doryen game window.
     pub new_height: u32,
 }
+
+/// The stage of a [`MouseDrag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragPhase {
+    /// The cursor left the origin cell while the button was held.
+    Start,
+    /// The cursor entered a new cell during the drag.
+    Move,
+    /// The button was released, ending the drag.
+    End,
+}
+
+/// Emitted while the mouse is moved over the console with a button held down.
+///
+/// A drag starts once the cursor leaves the cell where the button was pressed, so a plain
+/// click never emits these events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseDrag {
+    /// The button held during the drag.
+    pub button: MouseButton,
+    /// The stage of the drag.
+    pub phase: DragPhase,
+    /// The console cell where the button was pressed.
+    pub origin: (i32, i32),
+    /// The console cell under the cursor.
+    pub cell: (i32, i32),
+}
+
+/// Emitted when a mouse button is pressed twice on the same console cell within
+/// [`DOUBLE_CLICK_TIME`](crate::DOUBLE_CLICK_TIME).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseDoubleClick {
+    /// The button that was double clicked.
+    pub button: MouseButton,
+    /// The console cell that was double clicked.
+    pub cell: (i32, i32),
+}
+
+/// Emitted when the cursor moves to another console cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseCellEntered {
+    /// The console cell the cursor left.
+    pub previous: (i32, i32),
+    /// The console cell under the cursor.
+    pub cell: (i32, i32),
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Events;
+
+    use super::*;
+
+    fn font(name: &str, grid: (u32, u32), charset: Charset) -> FontInfo {
+        FontInfo {
+            name: name.to_string(),
+            path: format!("fonts/{name}.png"),
+            cell_size: (8, 8),
+            grid,
+            charset,
+            has_alpha: false,
+        }
+    }
+
+    fn forwarded(fonts: Vec<FontInfo>) -> Vec<String> {
+        let mut app = App::new();
+        app.add_plugin(DoryenEventPlugin);
+        for font in fonts {
+            app.world.send_event(ChangeFont(font));
+        }
+        app.update();
+        let events = app.world.resource::<Events<SetFontPath>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|SetFontPath(path)| path.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn forwards_the_latest_font() {
+        let fonts = vec![
+            font("terminal8x8", (16, 16), Charset::Cp437),
+            font("terminal8x16", (16, 16), Charset::Cp437),
+        ];
+        assert_eq!(forwarded(fonts), ["fonts/terminal8x16.png"]);
+    }
+
+    #[test]
+    fn ignores_other_grids() {
+        assert!(forwarded(vec![font("tiles", (32, 8), Charset::Cp437)]).is_empty());
+    }
+
+    #[test]
+    fn ignores_other_charsets() {
+        assert!(forwarded(vec![font("unifont", (16, 16), Charset::Unicode)]).is_empty());
+    }
+
+    #[test]
+    fn keeps_the_latest_supported_font() {
+        let fonts = vec![
+            font("terminal8x8", (16, 16), Charset::Cp437),
+            font("unifont", (16, 16), Charset::Unicode),
+        ];
+        assert_eq!(forwarded(fonts), ["fonts/terminal8x8.png"]);
+    }
+}
@@ -1,11 +1,21 @@
 //! Input handling for `doryten-rs` input back to bevy.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
-use bevy::prelude::{App, Plugin, Resource};
-use doryen_rs::{Keys, MouseButton, ScanCode};
+use bevy::{
+    prelude::{App, Events, MouseButton as BevyMouseButton, Plugin, Resource, World},
+    utils::Instant,
+};
+use brltk_common::{ActionInput, ConsoleInput, Modifiers, Repeat};
+use doryen_rs::{MouseButton, ScanCode};
 
-use crate::doryen::InputApi;
+use crate::{
+    scan_code_to_key_code, DragPhase, InputFrame, InputRecorder, MouseButtonNames,
+    MouseCellEntered, MouseDoubleClick, MouseDrag, ScanCodeNames,
+};
+
+/// The maximum time between the two presses of a [`MouseDoubleClick`].
+pub const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
 
 /// The Bevy Doryen input plugin to handle interloping
 /// input events from `doryen-rs` to `Bevy`.
@@ -13,13 +23,19 @@ use crate::doryen::InputApi;
 pub struct DoryenInputPlugin;
 impl Plugin for DoryenInputPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<DoryenInput>();
+        app.init_resource::<DoryenInput>()
+            .init_resource::<ConsoleInput>()
+            .init_resource::<InputRecorder>();
     }
 }
 
 /// Provides access to the input events handled by the Doryen engine. See the
-/// documentation for the [`InputApi`] type for details on what values should
-/// be used with the various `key` methods.
+/// documentation for the [`InputApi`](crate::doryen::InputApi) type for details
+/// on what values should be used with the various `key` methods.
+///
+/// Mouse drags, double clicks and cell changes are also sent as [`MouseDrag`],
+/// [`MouseDoubleClick`] and [`MouseCellEntered`] events. The mouse wheel is not reported
+/// yet: the [`InputApi`](crate::doryen::InputApi) of `doryen-rs` does not forward it.
 #[derive(Default, Debug, Resource)]
 pub struct DoryenInput {
     text: String,
@@ -28,11 +44,27 @@ pub struct DoryenInput {
     keys_down: HashMap<ScanCode, bool>,
     keys_pressed: HashMap<ScanCode, bool>,
     keys_released: HashMap<ScanCode, bool>,
+    keys_repeated: HashMap<ScanCode, bool>,
+    keys_next_repeat: HashMap<ScanCode, Instant>,
 
     mouse_position: (f32, f32),
     mouse_buttons_down: HashMap<MouseButton, bool>,
     mouse_buttons_pressed: HashMap<MouseButton, bool>,
     mouse_buttons_released: HashMap<MouseButton, bool>,
+
+    mouse_cell: Option<(i32, i32)>,
+    drags: HashMap<MouseButton, Drag>,
+    last_clicks: HashMap<MouseButton, (Instant, (i32, i32))>,
+    drag_events: Vec<MouseDrag>,
+    double_click_events: Vec<MouseDoubleClick>,
+    cell_entered_events: Vec<MouseCellEntered>,
+}
+
+/// A mouse button held down since it was pressed on `origin`.
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    origin: (i32, i32),
+    started: bool,
 }
 
 impl DoryenInput {
@@ -42,6 +74,7 @@ impl DoryenInput {
 
         self.keys_released.clear();
         self.keys_pressed.clear();
+        self.keys_repeated.clear();
 
         self.mouse_buttons_pressed.clear();
         self.mouse_buttons_released.clear();
@@ -49,18 +82,35 @@ impl DoryenInput {
 
     pub(crate) fn handle_input(
         &mut self,
-        mouse_button_listeners: &[MouseButton],
-        input: &mut dyn InputApi,
+        frame: &InputFrame,
+        key_repeat: Option<Repeat>,
+        now: Instant,
     ) {
         self.on_frame();
 
-        self.handle_pressed(input.keys_pressed());
-        self.handle_released(input.keys_released());
-        self.handle_mouse(mouse_button_listeners, input);
+        self.handle_pressed(&frame.keys_pressed);
+        self.handle_released(&frame.keys_released);
+        self.handle_repeat(key_repeat, now);
+        self.handle_mouse(frame);
+
+        self.text.clone_from(&frame.text);
+        if let Some(mouse_position) = frame.mouse_position {
+            self.mouse_position = mouse_position;
+        }
+        self.close_requested = frame.close_requested;
+
+        self.handle_mouse_events(now);
+    }
 
-        self.text = input.text();
-        self.mouse_position = input.mouse_pos();
-        self.close_requested = input.close_requested();
+    /// Hides the keyboard input of this frame from the game and releases the keys held down,
+    /// for a modal dialog taking the keyboard.
+    pub(crate) fn consume_keys(&mut self) {
+        self.text.clear();
+        self.keys_down.clear();
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.keys_repeated.clear();
+        self.keys_next_repeat.clear();
     }
 
     /// Returns the current status of the given key (true if currently pressed).
@@ -79,6 +129,13 @@ impl DoryenInput {
         self.keys_pressed.iter().filter(|&(_, &v)| v)
     }
 
+    /// Returns true if the given key was pressed since the last update by the key repeat
+    /// rather than by the user. Repeated presses are also reported by
+    /// [`key_pressed`](Self::key_pressed).
+    pub fn key_repeated(&self, scan_code: ScanCode) -> bool {
+        matches!(self.keys_repeated.get(&scan_code), Some(&true))
+    }
+
     /// Returns true if the given key was released since the last update.
     pub fn key_released(&self, scan_code: ScanCode) -> bool {
         matches!(self.keys_released.get(&scan_code), Some(&true))
@@ -119,15 +176,60 @@ impl DoryenInput {
         self.mouse_position
     }
 
+    /// Returns the console cell under the mouse.
+    pub fn mouse_cell(&self) -> (i32, i32) {
+        (
+            self.mouse_position.0.floor() as i32,
+            self.mouse_position.1.floor() as i32,
+        )
+    }
+
     /// Whether the window close button has been activated.
     pub fn close_requested(&self) -> bool {
         self.close_requested
     }
+
+    /// Returns the current state of the modifier keys, whichever side is pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            shift: self.key(ScanCode::LeftShift) || self.key(ScanCode::RightShift),
+            ctrl: self.key(ScanCode::LeftControl) || self.key(ScanCode::RightControl),
+            alt: self.key(ScanCode::LeftAlt) || self.key(ScanCode::RightAlt),
+        }
+    }
+}
+
+impl ActionInput for DoryenInput {
+    type Key = ScanCode;
+    type MouseButton = MouseButton;
+    type KeyNames = ScanCodeNames;
+    type MouseButtonNames = MouseButtonNames;
+
+    fn key_modifiers(scan_code: ScanCode) -> Modifiers {
+        match scan_code {
+            ScanCode::LeftShift | ScanCode::RightShift => Modifiers::SHIFT,
+            ScanCode::LeftControl | ScanCode::RightControl => Modifiers::CTRL,
+            ScanCode::LeftAlt | ScanCode::RightAlt => Modifiers::ALT,
+            _ => Modifiers::NONE,
+        }
+    }
+
+    fn key_down(&self, scan_code: ScanCode) -> bool {
+        self.key(scan_code)
+    }
+
+    fn mouse_button_down(&self, mouse_button: MouseButton) -> bool {
+        self.mouse_button(mouse_button)
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        DoryenInput::modifiers(self)
+    }
 }
 
 impl DoryenInput {
-    fn handle_pressed(&mut self, pressed: Keys<'_>) {
-        pressed.for_each(|k| {
+    fn handle_pressed(&mut self, pressed: &[ScanCode]) {
+        pressed.iter().for_each(|k| {
             if let Some(v) = self.keys_pressed.get_mut(k) {
                 *v = true;
             } else {
@@ -142,8 +244,8 @@ impl DoryenInput {
         });
     }
 
-    fn handle_released(&mut self, released: Keys<'_>) {
-        released.for_each(|k| {
+    fn handle_released(&mut self, released: &[ScanCode]) {
+        released.iter().for_each(|k| {
             if let Some(v) = self.keys_released.get_mut(k) {
                 *v = true;
             } else {
@@ -157,20 +259,398 @@ impl DoryenInput {
         });
     }
 
-    fn handle_mouse(&mut self, mouse_button_listeners: &[MouseButton], input: &mut dyn InputApi) {
-        mouse_button_listeners.iter().for_each(|&mouse_button| {
-            // On Down
-            if input.mouse_button_pressed(mouse_button) {
+    /// Emits a synthetic press for every key held past its next repeat time.
+    fn handle_repeat(&mut self, key_repeat: Option<Repeat>, now: Instant) {
+        let Some(Repeat { delay, interval }) = key_repeat else {
+            self.keys_next_repeat.clear();
+            return;
+        };
+
+        let keys_down: Vec<ScanCode> = self
+            .keys_down
+            .iter()
+            .filter(|&(_, &v)| v)
+            .map(|(&k, _)| k)
+            .collect();
+        self.keys_next_repeat.retain(|k, _| keys_down.contains(k));
+
+        for k in keys_down {
+            if self.key_pressed(k) {
+                self.keys_next_repeat.insert(k, now + delay);
+                continue;
+            }
+
+            let next_repeat = self.keys_next_repeat.entry(k).or_insert(now + delay);
+            if now >= *next_repeat {
+                // Skip the repeats missed during a long frame rather than firing them on
+                // every following frame.
+                *next_repeat += interval;
+                if *next_repeat <= now {
+                    *next_repeat = now + interval;
+                }
+                self.keys_pressed.insert(k, true);
+                self.keys_repeated.insert(k, true);
+            }
+        }
+    }
+
+    /// Reports the input of this frame to the backend agnostic [`ConsoleInput`].
+    pub(crate) fn sync_console_input(&self, console_input: &mut ConsoleInput) {
+        console_input.on_frame();
+
+        self.keys_pressed
+            .iter()
+            .filter(|&(_, &v)| v)
+            .filter_map(|(&k, _)| scan_code_to_key_code(k))
+            .for_each(|k| console_input.press_key(k));
+        self.keys_released
+            .iter()
+            .filter(|&(_, &v)| v)
+            .filter_map(|(&k, _)| scan_code_to_key_code(k))
+            .for_each(|k| console_input.release_key(k));
+
+        self.mouse_buttons_pressed
+            .iter()
+            .filter(|&(_, &v)| v)
+            .for_each(|(&b, _)| console_input.press_mouse_button(to_bevy_mouse_button(b)));
+        self.mouse_buttons_released
+            .iter()
+            .filter(|&(_, &v)| v)
+            .for_each(|(&b, _)| console_input.release_mouse_button(to_bevy_mouse_button(b)));
+
+        console_input.push_text(&self.text);
+        console_input.set_mouse_position(self.mouse_position);
+        if self.close_requested {
+            console_input.request_close();
+        }
+    }
+
+    /// Detects the drags, double clicks and cell changes of this frame.
+    fn handle_mouse_events(&mut self, now: Instant) {
+        let cell = self.mouse_cell();
+        let cell_entered = match self.mouse_cell.replace(cell) {
+            Some(previous) if previous != cell => {
+                self.cell_entered_events
+                    .push(MouseCellEntered { previous, cell });
+                true
+            }
+            _ => false,
+        };
+
+        let pressed: Vec<MouseButton> = self
+            .mouse_buttons_pressed
+            .iter()
+            .filter(|&(_, &v)| v)
+            .map(|(&b, _)| b)
+            .collect();
+        for button in pressed {
+            self.drags.insert(
+                button,
+                Drag {
+                    origin: cell,
+                    started: false,
+                },
+            );
+
+            match self.last_clicks.remove(&button) {
+                Some((time, click_cell))
+                    if click_cell == cell && now.duration_since(time) <= DOUBLE_CLICK_TIME =>
+                {
+                    self.double_click_events
+                        .push(MouseDoubleClick { button, cell });
+                }
+                _ => {
+                    self.last_clicks.insert(button, (now, cell));
+                }
+            }
+        }
+
+        if cell_entered {
+            for (&button, drag) in &mut self.drags {
+                let phase = if drag.started {
+                    DragPhase::Move
+                } else if cell != drag.origin {
+                    drag.started = true;
+                    DragPhase::Start
+                } else {
+                    continue;
+                };
+                self.drag_events.push(MouseDrag {
+                    button,
+                    phase,
+                    origin: drag.origin,
+                    cell,
+                });
+            }
+        }
+
+        let released: Vec<MouseButton> = self
+            .mouse_buttons_released
+            .iter()
+            .filter(|&(_, &v)| v)
+            .map(|(&b, _)| b)
+            .collect();
+        for button in released {
+            if let Some(Drag {
+                origin,
+                started: true,
+            }) = self.drags.remove(&button)
+            {
+                self.drag_events.push(MouseDrag {
+                    button,
+                    phase: DragPhase::End,
+                    origin,
+                    cell,
+                });
+            }
+        }
+    }
+
+    /// Sends the mouse events detected since the last call as Bevy events.
+    pub(crate) fn send_mouse_events(&mut self, world: &mut World) {
+        world
+            .resource_mut::<Events<MouseDrag>>()
+            .extend(self.drag_events.drain(..));
+        world
+            .resource_mut::<Events<MouseDoubleClick>>()
+            .extend(self.double_click_events.drain(..));
+        world
+            .resource_mut::<Events<MouseCellEntered>>()
+            .extend(self.cell_entered_events.drain(..));
+    }
+
+    fn handle_mouse(&mut self, frame: &InputFrame) {
+        // On Down
+        frame
+            .mouse_buttons_pressed
+            .iter()
+            .for_each(|&mouse_button| {
                 self.mouse_buttons_pressed.insert(mouse_button, true);
                 self.mouse_buttons_down.insert(mouse_button, true);
-            }
+            });
 
-            // On Release
-            if input.mouse_button_released(mouse_button) {
+        // On Release
+        frame
+            .mouse_buttons_released
+            .iter()
+            .for_each(|&mouse_button| {
                 self.mouse_buttons_down.insert(mouse_button, false);
                 self.mouse_buttons_pressed.insert(mouse_button, false);
                 self.mouse_buttons_released.insert(mouse_button, true);
+            });
+    }
+}
+
+fn to_bevy_mouse_button(mouse_button: MouseButton) -> BevyMouseButton {
+    match mouse_button {
+        MouseButton::Left => BevyMouseButton::Left,
+        MouseButton::Middle => BevyMouseButton::Middle,
+        MouseButton::Right => BevyMouseButton::Right,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPEAT: Repeat = Repeat {
+        delay: Duration::from_millis(300),
+        interval: Duration::from_millis(100),
+    };
+
+    fn pressed(scan_code: ScanCode) -> InputFrame {
+        InputFrame {
+            keys_pressed: vec![scan_code],
+            ..Default::default()
+        }
+    }
+
+    fn mouse(
+        position: (f32, f32),
+        pressed: &[MouseButton],
+        released: &[MouseButton],
+    ) -> InputFrame {
+        InputFrame {
+            mouse_position: Some(position),
+            mouse_buttons_pressed: pressed.to_vec(),
+            mouse_buttons_released: released.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn released(scan_code: ScanCode) -> InputFrame {
+        InputFrame {
+            keys_released: vec![scan_code],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn repeats_after_the_delay() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut input = DoryenInput::default();
+
+        input.handle_input(&pressed(ScanCode::Up), Some(REPEAT), at(0));
+        assert!(input.key_pressed(ScanCode::Up));
+        assert!(!input.key_repeated(ScanCode::Up));
+
+        let mut repeats = Vec::new();
+        for millis in (50..=550).step_by(50) {
+            input.handle_input(&InputFrame::default(), Some(REPEAT), at(millis));
+            assert_eq!(
+                input.key_pressed(ScanCode::Up),
+                input.key_repeated(ScanCode::Up)
+            );
+            if input.key_repeated(ScanCode::Up) {
+                repeats.push(millis);
             }
-        });
+        }
+        assert_eq!(repeats, [300, 400, 500]);
+
+        input.handle_input(&released(ScanCode::Up), Some(REPEAT), at(600));
+        assert!(input.key_released(ScanCode::Up));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(1000));
+        assert!(!input.key_pressed(ScanCode::Up));
+    }
+
+    #[test]
+    fn skips_the_repeats_missed_during_a_long_frame() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut input = DoryenInput::default();
+
+        input.handle_input(&pressed(ScanCode::Down), Some(REPEAT), at(0));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(1000));
+        assert!(input.key_repeated(ScanCode::Down));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(1050));
+        assert!(!input.key_repeated(ScanCode::Down));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(1100));
+        assert!(input.key_repeated(ScanCode::Down));
+    }
+
+    #[test]
+    fn a_new_press_restarts_the_delay() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut input = DoryenInput::default();
+
+        input.handle_input(&pressed(ScanCode::A), Some(REPEAT), at(0));
+        input.handle_input(&pressed(ScanCode::A), Some(REPEAT), at(200));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(400));
+        assert!(!input.key_repeated(ScanCode::A));
+        input.handle_input(&InputFrame::default(), Some(REPEAT), at(500));
+        assert!(input.key_repeated(ScanCode::A));
+    }
+
+    #[test]
+    fn no_repeat_without_a_setting() {
+        let start = Instant::now();
+        let mut input = DoryenInput::default();
+
+        input.handle_input(&pressed(ScanCode::Space), None, start);
+        input.handle_input(&InputFrame::default(), None, start + Duration::from_secs(2));
+        assert!(input.key(ScanCode::Space));
+        assert!(!input.key_pressed(ScanCode::Space));
+        assert!(!input.key_repeated(ScanCode::Space));
+    }
+
+    #[test]
+    fn drags_from_the_pressed_cell() {
+        let now = Instant::now();
+        let mut input = DoryenInput::default();
+
+        input.handle_input(&mouse((1.5, 1.5), &[MouseButton::Left], &[]), None, now);
+        input.handle_input(&mouse((1.9, 1.2), &[], &[]), None, now);
+        assert!(input.drag_events.is_empty());
+        input.handle_input(&mouse((2.5, 1.5), &[], &[]), None, now);
+        input.handle_input(&mouse((3.5, 2.5), &[], &[]), None, now);
+        input.handle_input(&mouse((3.5, 2.5), &[], &[MouseButton::Left]), None, now);
+
+        let drag = |phase, cell| MouseDrag {
+            button: MouseButton::Left,
+            phase,
+            origin: (1, 1),
+            cell,
+        };
+        assert_eq!(
+            input.drag_events,
+            [
+                drag(DragPhase::Start, (2, 1)),
+                drag(DragPhase::Move, (3, 2)),
+                drag(DragPhase::End, (3, 2)),
+            ]
+        );
+        assert_eq!(
+            input.cell_entered_events,
+            [
+                MouseCellEntered {
+                    previous: (1, 1),
+                    cell: (2, 1),
+                },
+                MouseCellEntered {
+                    previous: (2, 1),
+                    cell: (3, 2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn double_clicks_on_the_same_cell() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let click = |input: &mut DoryenInput, position, millis| {
+            input.handle_input(
+                &mouse(position, &[MouseButton::Right], &[]),
+                None,
+                at(millis),
+            );
+            input.handle_input(
+                &mouse(position, &[], &[MouseButton::Right]),
+                None,
+                at(millis),
+            );
+        };
+        let mut input = DoryenInput::default();
+
+        click(&mut input, (4.0, 4.0), 0);
+        click(&mut input, (4.5, 4.5), 200);
+        click(&mut input, (4.5, 4.5), 400);
+        click(&mut input, (5.0, 4.0), 500);
+        click(&mut input, (5.0, 4.0), 2000);
+
+        assert_eq!(
+            input.double_click_events,
+            [MouseDoubleClick {
+                button: MouseButton::Right,
+                cell: (4, 4),
+            }]
+        );
+    }
+
+    #[test]
+    fn input_map_ron_round_trip() {
+        use brltk_common::{Binding, InputMap, Trigger};
+
+        let input_map = InputMap::<String, DoryenInput>::default()
+            .with_binding("wait".to_string(), Binding::key(ScanCode::Kp5))
+            .with_binding(
+                "look".to_string(),
+                Binding::chord([
+                    Trigger::Key(ScanCode::Semicolon),
+                    Trigger::Mouse(MouseButton::Right),
+                ])
+                .with_modifiers(Modifiers::SHIFT),
+            );
+
+        let ron = input_map.to_ron_string().unwrap();
+        assert!(ron.contains(r#"Key("Kp5")"#));
+        assert!(ron.contains(r#"Mouse("Right")"#));
+        let parsed = InputMap::<String, DoryenInput>::from_ron_str(&ron).unwrap();
+        for (action, bindings) in input_map.iter() {
+            assert_eq!(parsed.bindings(action), bindings);
+        }
+        assert_eq!(parsed.to_ron_string().unwrap(), ron);
     }
 }
@@ -0,0 +1,269 @@
+//! The names of the Doryen keys and mouse buttons, and their Bevy equivalents.
+
+use std::borrow::Cow;
+
+use bevy::prelude::KeyCode;
+use brltk_common::Names;
+use doryen_rs::{MouseButton, ScanCode};
+
+/// Every Doryen [`ScanCode`], with its name and the matching Bevy [`KeyCode`].
+const SCAN_CODES: &[(ScanCode, &str, Option<KeyCode>)] = &[
+    (ScanCode::Space, "Space", Some(KeyCode::Space)),
+    (
+        ScanCode::Apostrophe,
+        "Apostrophe",
+        Some(KeyCode::Apostrophe),
+    ),
+    (ScanCode::Comma, "Comma", Some(KeyCode::Comma)),
+    (ScanCode::Minus, "Minus", Some(KeyCode::Minus)),
+    (ScanCode::Period, "Period", Some(KeyCode::Period)),
+    (ScanCode::Slash, "Slash", Some(KeyCode::Slash)),
+    (ScanCode::Key0, "Key0", Some(KeyCode::Key0)),
+    (ScanCode::Key1, "Key1", Some(KeyCode::Key1)),
+    (ScanCode::Key2, "Key2", Some(KeyCode::Key2)),
+    (ScanCode::Key3, "Key3", Some(KeyCode::Key3)),
+    (ScanCode::Key4, "Key4", Some(KeyCode::Key4)),
+    (ScanCode::Key5, "Key5", Some(KeyCode::Key5)),
+    (ScanCode::Key6, "Key6", Some(KeyCode::Key6)),
+    (ScanCode::Key7, "Key7", Some(KeyCode::Key7)),
+    (ScanCode::Key8, "Key8", Some(KeyCode::Key8)),
+    (ScanCode::Key9, "Key9", Some(KeyCode::Key9)),
+    (ScanCode::Semicolon, "Semicolon", Some(KeyCode::Semicolon)),
+    (ScanCode::Equal, "Equal", Some(KeyCode::Equals)),
+    (ScanCode::A, "A", Some(KeyCode::A)),
+    (ScanCode::B, "B", Some(KeyCode::B)),
+    (ScanCode::C, "C", Some(KeyCode::C)),
+    (ScanCode::D, "D", Some(KeyCode::D)),
+    (ScanCode::E, "E", Some(KeyCode::E)),
+    (ScanCode::F, "F", Some(KeyCode::F)),
+    (ScanCode::G, "G", Some(KeyCode::G)),
+    (ScanCode::H, "H", Some(KeyCode::H)),
+    (ScanCode::I, "I", Some(KeyCode::I)),
+    (ScanCode::J, "J", Some(KeyCode::J)),
+    (ScanCode::K, "K", Some(KeyCode::K)),
+    (ScanCode::L, "L", Some(KeyCode::L)),
+    (ScanCode::M, "M", Some(KeyCode::M)),
+    (ScanCode::N, "N", Some(KeyCode::N)),
+    (ScanCode::O, "O", Some(KeyCode::O)),
+    (ScanCode::P, "P", Some(KeyCode::P)),
+    (ScanCode::Q, "Q", Some(KeyCode::Q)),
+    (ScanCode::R, "R", Some(KeyCode::R)),
+    (ScanCode::S, "S", Some(KeyCode::S)),
+    (ScanCode::T, "T", Some(KeyCode::T)),
+    (ScanCode::U, "U", Some(KeyCode::U)),
+    (ScanCode::V, "V", Some(KeyCode::V)),
+    (ScanCode::W, "W", Some(KeyCode::W)),
+    (ScanCode::X, "X", Some(KeyCode::X)),
+    (ScanCode::Y, "Y", Some(KeyCode::Y)),
+    (ScanCode::Z, "Z", Some(KeyCode::Z)),
+    (
+        ScanCode::LeftBracket,
+        "LeftBracket",
+        Some(KeyCode::LBracket),
+    ),
+    (ScanCode::Backslash, "Backslash", Some(KeyCode::Backslash)),
+    (
+        ScanCode::RightBracket,
+        "RightBracket",
+        Some(KeyCode::RBracket),
+    ),
+    (ScanCode::GraveAccent, "GraveAccent", Some(KeyCode::Grave)),
+    (ScanCode::World1, "World1", None),
+    (ScanCode::World2, "World2", None),
+    (ScanCode::Escape, "Escape", Some(KeyCode::Escape)),
+    (ScanCode::Enter, "Enter", Some(KeyCode::Return)),
+    (ScanCode::Tab, "Tab", Some(KeyCode::Tab)),
+    (ScanCode::Backspace, "Backspace", Some(KeyCode::Back)),
+    (ScanCode::Insert, "Insert", Some(KeyCode::Insert)),
+    (ScanCode::Delete, "Delete", Some(KeyCode::Delete)),
+    (ScanCode::Right, "Right", Some(KeyCode::Right)),
+    (ScanCode::Left, "Left", Some(KeyCode::Left)),
+    (ScanCode::Down, "Down", Some(KeyCode::Down)),
+    (ScanCode::Up, "Up", Some(KeyCode::Up)),
+    (ScanCode::PageUp, "PageUp", Some(KeyCode::PageUp)),
+    (ScanCode::PageDown, "PageDown", Some(KeyCode::PageDown)),
+    (ScanCode::Home, "Home", Some(KeyCode::Home)),
+    (ScanCode::End, "End", Some(KeyCode::End)),
+    (ScanCode::CapsLock, "CapsLock", Some(KeyCode::Capital)),
+    (ScanCode::ScrollLock, "ScrollLock", Some(KeyCode::Scroll)),
+    (ScanCode::NumLock, "NumLock", Some(KeyCode::Numlock)),
+    (
+        ScanCode::PrintScreen,
+        "PrintScreen",
+        Some(KeyCode::Snapshot),
+    ),
+    (ScanCode::Pause, "Pause", Some(KeyCode::Pause)),
+    (ScanCode::F1, "F1", Some(KeyCode::F1)),
+    (ScanCode::F2, "F2", Some(KeyCode::F2)),
+    (ScanCode::F3, "F3", Some(KeyCode::F3)),
+    (ScanCode::F4, "F4", Some(KeyCode::F4)),
+    (ScanCode::F5, "F5", Some(KeyCode::F5)),
+    (ScanCode::F6, "F6", Some(KeyCode::F6)),
+    (ScanCode::F7, "F7", Some(KeyCode::F7)),
+    (ScanCode::F8, "F8", Some(KeyCode::F8)),
+    (ScanCode::F9, "F9", Some(KeyCode::F9)),
+    (ScanCode::F10, "F10", Some(KeyCode::F10)),
+    (ScanCode::F11, "F11", Some(KeyCode::F11)),
+    (ScanCode::F12, "F12", Some(KeyCode::F12)),
+    (ScanCode::F13, "F13", Some(KeyCode::F13)),
+    (ScanCode::F14, "F14", Some(KeyCode::F14)),
+    (ScanCode::F15, "F15", Some(KeyCode::F15)),
+    (ScanCode::F16, "F16", Some(KeyCode::F16)),
+    (ScanCode::F17, "F17", Some(KeyCode::F17)),
+    (ScanCode::F18, "F18", Some(KeyCode::F18)),
+    (ScanCode::F19, "F19", Some(KeyCode::F19)),
+    (ScanCode::F20, "F20", Some(KeyCode::F20)),
+    (ScanCode::F21, "F21", Some(KeyCode::F21)),
+    (ScanCode::F22, "F22", Some(KeyCode::F22)),
+    (ScanCode::F23, "F23", Some(KeyCode::F23)),
+    (ScanCode::F24, "F24", Some(KeyCode::F24)),
+    (ScanCode::F25, "F25", None),
+    (ScanCode::Kp0, "Kp0", Some(KeyCode::Numpad0)),
+    (ScanCode::Kp1, "Kp1", Some(KeyCode::Numpad1)),
+    (ScanCode::Kp2, "Kp2", Some(KeyCode::Numpad2)),
+    (ScanCode::Kp3, "Kp3", Some(KeyCode::Numpad3)),
+    (ScanCode::Kp4, "Kp4", Some(KeyCode::Numpad4)),
+    (ScanCode::Kp5, "Kp5", Some(KeyCode::Numpad5)),
+    (ScanCode::Kp6, "Kp6", Some(KeyCode::Numpad6)),
+    (ScanCode::Kp7, "Kp7", Some(KeyCode::Numpad7)),
+    (ScanCode::Kp8, "Kp8", Some(KeyCode::Numpad8)),
+    (ScanCode::Kp9, "Kp9", Some(KeyCode::Numpad9)),
+    (
+        ScanCode::KpDecimal,
+        "KpDecimal",
+        Some(KeyCode::NumpadDecimal),
+    ),
+    (ScanCode::KpDivide, "KpDivide", Some(KeyCode::NumpadDivide)),
+    (
+        ScanCode::KpMultiply,
+        "KpMultiply",
+        Some(KeyCode::NumpadMultiply),
+    ),
+    (
+        ScanCode::KpSubtract,
+        "KpSubtract",
+        Some(KeyCode::NumpadSubtract),
+    ),
+    (ScanCode::KpAdd, "KpAdd", Some(KeyCode::NumpadAdd)),
+    (ScanCode::KpEnter, "KpEnter", Some(KeyCode::NumpadEnter)),
+    (ScanCode::KpEqual, "KpEqual", Some(KeyCode::NumpadEquals)),
+    (ScanCode::LeftShift, "LeftShift", Some(KeyCode::LShift)),
+    (
+        ScanCode::LeftControl,
+        "LeftControl",
+        Some(KeyCode::LControl),
+    ),
+    (ScanCode::LeftAlt, "LeftAlt", Some(KeyCode::LAlt)),
+    (ScanCode::LeftSuper, "LeftSuper", Some(KeyCode::LWin)),
+    (ScanCode::RightShift, "RightShift", Some(KeyCode::RShift)),
+    (
+        ScanCode::RightControl,
+        "RightControl",
+        Some(KeyCode::RControl),
+    ),
+    (ScanCode::RightAlt, "RightAlt", Some(KeyCode::RAlt)),
+    (ScanCode::RightSuper, "RightSuper", Some(KeyCode::RWin)),
+    (ScanCode::Menu, "Menu", Some(KeyCode::Apps)),
+    (ScanCode::Unknown, "Unknown", None),
+];
+
+/// Every Doryen [`MouseButton`], with its name.
+const MOUSE_BUTTONS: &[(MouseButton, &str)] = &[
+    (MouseButton::Left, "Left"),
+    (MouseButton::Middle, "Middle"),
+    (MouseButton::Right, "Right"),
+];
+
+/// Names the Doryen [`ScanCode`]s, which do not implement serde, by their variant.
+pub struct ScanCodeNames;
+
+impl Names for ScanCodeNames {
+    type Value = ScanCode;
+
+    fn name(scan_code: ScanCode) -> Option<Cow<'static, str>> {
+        SCAN_CODES
+            .iter()
+            .find(|(s, _, _)| *s == scan_code)
+            .map(|(_, name, _)| Cow::Borrowed(*name))
+    }
+
+    fn from_name(name: &str) -> Option<ScanCode> {
+        SCAN_CODES
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(scan_code, _, _)| *scan_code)
+    }
+}
+
+/// Names the Doryen [`MouseButton`]s, which do not implement serde, by their variant.
+pub struct MouseButtonNames;
+
+impl Names for MouseButtonNames {
+    type Value = MouseButton;
+
+    fn name(mouse_button: MouseButton) -> Option<Cow<'static, str>> {
+        MOUSE_BUTTONS
+            .iter()
+            .find(|(b, _)| *b == mouse_button)
+            .map(|(_, name)| Cow::Borrowed(*name))
+    }
+
+    fn from_name(name: &str) -> Option<MouseButton> {
+        MOUSE_BUTTONS
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(mouse_button, _)| *mouse_button)
+    }
+}
+
+/// Returns the Bevy [`KeyCode`] matching a Doryen [`ScanCode`], as reported in the
+/// [`ConsoleInput`](brltk_common::ConsoleInput) resource. Keys without an equivalent return
+/// `None`.
+pub fn scan_code_to_key_code(scan_code: ScanCode) -> Option<KeyCode> {
+    SCAN_CODES
+        .iter()
+        .find(|(s, _, _)| *s == scan_code)
+        .and_then(|(_, _, key_code)| *key_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn names_are_unique() {
+        let scan_codes: HashSet<_> = SCAN_CODES.iter().map(|(s, _, _)| *s).collect();
+        let names: HashSet<_> = SCAN_CODES.iter().map(|(_, n, _)| *n).collect();
+        assert_eq!(scan_codes.len(), SCAN_CODES.len());
+        assert_eq!(names.len(), SCAN_CODES.len());
+    }
+
+    #[test]
+    fn names_are_the_variants() {
+        for &(scan_code, name, _) in SCAN_CODES {
+            assert_eq!(format!("{scan_code:?}"), name);
+            assert_eq!(ScanCodeNames::from_name(name), Some(scan_code));
+        }
+        for &(mouse_button, name) in MOUSE_BUTTONS {
+            assert_eq!(format!("{mouse_button:?}"), name);
+            assert_eq!(MouseButtonNames::from_name(name), Some(mouse_button));
+        }
+        assert_eq!(ScanCodeNames::from_name("Hyper"), None);
+    }
+
+    #[test]
+    fn key_codes() {
+        assert_eq!(
+            scan_code_to_key_code(ScanCode::Enter),
+            Some(KeyCode::Return)
+        );
+        assert_eq!(scan_code_to_key_code(ScanCode::Kp7), Some(KeyCode::Numpad7));
+        assert_eq!(
+            scan_code_to_key_code(ScanCode::Period),
+            Some(KeyCode::Period)
+        );
+        assert_eq!(scan_code_to_key_code(ScanCode::Unknown), None);
+    }
+}
@@ -8,16 +8,21 @@
 #![warn(missing_docs)]
 
 use bevy::{ecs::event::ManualEventReader, prelude::App as BevyApp};
-use brltk_common::Backend;
+use brltk_common::{AppLifecycle, Backend, BackendCapabilities, FontRegistry, Repeat};
 use doryen_rs::{App as DoryenApp, Console, MouseButton};
 
 use crate::engine::DoryenEngine;
 
+mod assets;
+mod close;
+mod console;
 mod engine;
 mod event;
 mod input;
+mod keys;
 mod options;
 mod render;
+mod replay;
 
 /// Re-export of the Doryen library types.
 pub mod doryen {
@@ -26,11 +31,15 @@ pub mod doryen {
 
 /// Crate prelude.
 pub use crate::{
+    assets::*,
+    close::*,
     doryen::{AppOptions as DoryenAppOptions, ScanCode as VirtualScanCode},
     event::*,
     input::*,
+    keys::*,
     options::*,
     render::*,
+    replay::*,
 };
 
 /// The `doryen-rs` backend.
@@ -46,23 +55,38 @@ pub struct DoryenBackend {
     /// input handling.
     /// Defaults to left, middle and right mouse buttons.
     pub mouse_button_listeners: Vec<MouseButton>,
+
+    /// The delay and interval of the synthetic presses sent while a key is held, see
+    /// [`DoryenInput::key_repeated`]. `None` disables the key repeat.
+    /// Defaults to `None`.
+    pub key_repeat: Option<Repeat>,
+
+    /// What to do when the user asks to close the window.
+    /// Defaults to [`CloseRequestPolicy::Exit`].
+    pub close_request_policy: CloseRequestPolicy,
 }
 
 impl Backend for DoryenBackend {
     fn build(&self, app: &mut bevy::app::App) {
         // Resources
         app.init_resource::<FpsInfo>()
-            .init_resource::<RootConsole>();
+            .init_resource::<RootConsole>()
+            .init_resource::<CloseConfirmation>();
 
-        app.add_plugin(crate::event::DoryenEventPlugin)
+        app.add_plugin(crate::assets::DoryenAssetPlugin)
+            .add_plugin(crate::event::DoryenEventPlugin)
             .add_plugin(crate::input::DoryenInputPlugin)
             .add_plugin(crate::render::DoryenRenderPlugin);
 
+        let font = DoryenFont::new(&app.world, self.app_options.font_path.clone().into());
+        app.insert_resource(font);
+
         let Self {
             app_options,
             resize_mode,
             mouse_button_listeners,
-            ..
+            key_repeat,
+            close_request_policy,
         } = self.clone();
 
         app.set_runner(move |app| {
@@ -71,9 +95,35 @@ impl Backend for DoryenBackend {
                 app_options.clone(),
                 resize_mode,
                 mouse_button_listeners.clone(),
+                key_repeat,
+                close_request_policy,
             )
         });
     }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            name: "doryen",
+            subcell: true,
+            alpha_blending: true,
+            true_color: true,
+            named_colors: false,
+            // The offscreen consoles are blitted on the root console, skipping the cells of
+            // their key color.
+            layers: true,
+            sparse_layers: true,
+            // Every console is drawn with the font of the root console.
+            multiple_fonts: false,
+            runtime_font_change: true,
+            runtime_resize: !matches!(self.resize_mode, ResizeMode::Nothing),
+            borders: false,
+            windowed: true,
+        }
+    }
+
+    fn on_lifecycle(&self, world: &mut bevy::prelude::World, event: AppLifecycle) {
+        crate::close::handle_lifecycle(world, event);
+    }
 }
 
 impl std::fmt::Debug for DoryenBackend {
@@ -82,10 +132,24 @@ impl std::fmt::Debug for DoryenBackend {
             .field("app_options", &self.app_options)
             .field("resize_mode", &self.resize_mode)
             .field("mouse_button_listeners", &self.mouse_button_listeners)
+            .field("key_repeat", &self.key_repeat)
+            .field("close_request_policy", &self.close_request_policy)
             .finish()
     }
 }
 
+impl DoryenBackend {
+    /// Use a font of the [`FontRegistry`] as the initial font.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no font is registered under this name.
+    pub fn with_registered_font(mut self, registry: &FontRegistry, name: &str) -> Self {
+        self.app_options.font_path = registry.expect(name).path.clone();
+        self
+    }
+}
+
 impl Default for DoryenBackend {
     fn default() -> Self {
         Self {
@@ -96,6 +160,8 @@ impl Default for DoryenBackend {
                 MouseButton::Middle,
                 MouseButton::Right,
             ],
+            key_repeat: None,
+            close_request_policy: CloseRequestPolicy::Exit,
         }
     }
 }
@@ -105,6 +171,8 @@ fn doryen_runner(
     app_options: DoryenAppOptions,
     resize_mode: ResizeMode,
     mouse_button_listeners: Vec<MouseButton>,
+    key_repeat: Option<Repeat>,
+    close_request_policy: CloseRequestPolicy,
 ) {
     let DoryenAppOptions {
         screen_width,
@@ -114,9 +182,19 @@ fn doryen_runner(
         ..
     } = app_options;
 
+    // Doryen would close the window without running the app again: the close requests
+    // are always intercepted and handled by the `CloseRequestPolicy`, so that
+    // `AppLifecycle::Exiting` is sent before exiting.
+    let close_request_policy = match close_request_policy {
+        CloseRequestPolicy::Exit if app_options.intercept_close_request => {
+            CloseRequestPolicy::Event
+        }
+        close_request_policy => close_request_policy,
+    };
     let app_options = DoryenAppOptions {
-        /// The font path is relative to the `assets` directory.
-        font_path: format!("assets/{}", app_options.font_path),
+        // The font path is relative to the asset folder.
+        font_path: crate::assets::asset_path(&bevy_app.world, &app_options.font_path),
+        intercept_close_request: true,
         ..app_options
     };
 
@@ -127,6 +205,9 @@ fn doryen_runner(
             bevy_app,
             resize_mode,
             mouse_button_listeners,
+            key_repeat,
+            time_pinned: false,
+            close_request_policy,
             swap_console: Some(Console::new(1, 1)),
             previous_screen_size: (screen_width, screen_height),
             app_exit_event_reader: ManualEventReader::default(),
@@ -57,6 +57,26 @@ pub struct FpsInfo {
     pub average_fps: u32,
 }
 
+/// How the application reacts when the user asks to close the window. A
+/// [`WindowCloseRequested`] event is sent in every case.
+///
+/// Doryen is always told to intercept the close requests, whatever the
+/// [`intercept_close_request`](DoryenAppOptions::intercept_close_request) option says.
+/// Setting that option with the default policy behaves like [`CloseRequestPolicy::Event`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CloseRequestPolicy {
+    /// Exit the app. This is the default behavior.
+    #[default]
+    Exit,
+
+    /// Only send the event, the game decides when to send an
+    /// [`AppExit`](bevy::app::AppExit) event.
+    Event,
+
+    /// Show the [`CloseConfirmation`] dialog, and exit once it is confirmed.
+    Confirm,
+}
+
 /// How the application reacts to the resize event from `doryen-rs`.
 #[derive(Clone, Copy, Default)]
 pub enum ResizeMode {
@@ -65,7 +85,8 @@ pub enum ResizeMode {
 
     /// Set the console size to match the window size automatically. This
     /// retains the ratio defined between the console size and the screen size
-    /// as given in the [`DoryenAppOptions`] at the start of the program.
+    /// as given in the [`DoryenAppOptions`] at the start of the program. When the font
+    /// changes, the console is resized to keep the cells at the same zoom in the window.
     #[default]
     Automatic,
 
@@ -8,13 +8,30 @@ mod state;
 pub use state::*;
 mod image;
 pub use self::image::*;
+mod offscreen;
+pub use offscreen::*;
+mod dirty;
+pub use dirty::*;
 
 /// Render plugin for Bevy Doryen.
 pub struct DoryenRenderPlugin;
 impl Plugin for DoryenRenderPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<RenderState>()
-            .init_resource::<DoryenRenderSystems>();
+            .init_resource::<DoryenRenderSystems>()
+            .init_resource::<DirtyRegions>()
+            .add_doryen_render_system_to_stage(
+                DoryenRenderStage::First,
+                offscreen::clear_composited_regions,
+            )
+            .add_doryen_render_system_to_stage(
+                DoryenRenderStage::PostRender,
+                offscreen::blit_doryen_consoles,
+            )
+            .add_doryen_render_system_to_stage(
+                DoryenRenderStage::Last,
+                crate::close::render_close_confirmation,
+            );
     }
 }
 
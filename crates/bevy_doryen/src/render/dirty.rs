@@ -0,0 +1,363 @@
+use bevy::{
+    ecs::schedule::ShouldRun,
+    prelude::{Res, Resource},
+};
+use doryen_rs::{Color, Console};
+
+/// A rectangle of console cells.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellRect {
+    /// The column of the left edge.
+    pub x: i32,
+    /// The row of the top edge.
+    pub y: i32,
+    /// The width in cells.
+    pub width: u32,
+    /// The height in cells.
+    pub height: u32,
+}
+
+impl CellRect {
+    /// Create a rectangle from its top left corner and size.
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Create a rectangle covering a single cell.
+    pub fn cell(x: i32, y: i32) -> Self {
+        Self::new(x, y, 1, 1)
+    }
+
+    /// Returns true if the cell is inside the rectangle.
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+
+    /// Returns true if both rectangles share at least one cell.
+    pub fn intersects(&self, other: &CellRect) -> bool {
+        self.x < other.x + other.width as i32
+            && other.x < self.x + self.width as i32
+            && self.y < other.y + other.height as i32
+            && other.y < self.y + self.height as i32
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &CellRect) -> CellRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width as i32).max(other.x + other.width as i32);
+        let bottom = (self.y + self.height as i32).max(other.y + other.height as i32);
+        CellRect::new(x, y, (right - x) as u32, (bottom - y) as u32)
+    }
+}
+
+/// Tracks the regions of the [`RootConsole`](crate::RootConsole) that need to be redrawn,
+/// and optionally the cells that actually changed during the last render.
+///
+/// Doryen keeps the content of the root console between frames, so render systems can skip
+/// the regions that are not dirty. Mark regions dirty during the update, when the state they
+/// display changes, and check them in the render systems. Everything is dirty on the first
+/// frame and after the window is resized. The regions covered by the
+/// [`DoryenConsole`](crate::DoryenConsole)s during the previous render are dirty too, and
+/// cleared before the render systems run. The dirty regions are cleared after each render.
+///
+/// Comparing the whole console after each render to find the changed cells is opt-in, see
+/// [`with_change_tracking`](Self::with_change_tracking).
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_doryen::{CellRect, DirtyRegions, RootConsole};
+///
+/// fn render_sidebar(mut root_console: ResMut<RootConsole>, dirty: Res<DirtyRegions>) {
+///     let sidebar = CellRect::new(60, 0, 20, 45);
+///     if !dirty.is_dirty(&sidebar) {
+///         return;
+///     }
+///     // redraw the sidebar
+/// }
+/// ```
+#[derive(Debug, Resource)]
+pub struct DirtyRegions {
+    all_dirty: bool,
+    regions: Vec<CellRect>,
+    composited: Vec<CellRect>,
+
+    track_changes: bool,
+    size: (u32, u32),
+    ascii: Vec<u32>,
+    fore: Vec<Color>,
+    back: Vec<Color>,
+    changed: Vec<bool>,
+    changed_bounds: Option<CellRect>,
+}
+
+impl Default for DirtyRegions {
+    fn default() -> Self {
+        Self {
+            all_dirty: true,
+            regions: Vec::new(),
+            composited: Vec::new(),
+            track_changes: false,
+            size: (0, 0),
+            ascii: Vec::new(),
+            fore: Vec::new(),
+            back: Vec::new(),
+            changed: Vec::new(),
+            changed_bounds: None,
+        }
+    }
+}
+
+impl DirtyRegions {
+    /// Track the cells changed by each render. Insert the resource before the Doryen backend
+    /// to track them from the first frame:
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_doryen::DirtyRegions;
+    /// App::new().insert_resource(DirtyRegions::default().with_change_tracking());
+    /// ```
+    pub fn with_change_tracking(mut self) -> Self {
+        self.set_change_tracking(true);
+        self
+    }
+
+    /// Enable or disable the tracking of the cells changed by each render. Once enabled,
+    /// every cell is reported as changed by the next render.
+    pub fn set_change_tracking(&mut self, enabled: bool) {
+        self.track_changes = enabled;
+        if !enabled {
+            self.size = (0, 0);
+            self.ascii = Vec::new();
+            self.fore = Vec::new();
+            self.back = Vec::new();
+            self.changed = Vec::new();
+            self.changed_bounds = None;
+        }
+    }
+
+    /// Returns true if the cells changed by each render are tracked.
+    pub fn is_tracking_changes(&self) -> bool {
+        self.track_changes
+    }
+
+    /// Mark a region to be redrawn during the next render.
+    pub fn mark_dirty(&mut self, rect: CellRect) {
+        if !self.all_dirty {
+            self.regions.push(rect);
+        }
+    }
+
+    /// Mark a single cell to be redrawn during the next render.
+    pub fn mark_cell_dirty(&mut self, x: i32, y: i32) {
+        self.mark_dirty(CellRect::cell(x, y));
+    }
+
+    /// Mark the whole console to be redrawn during the next render.
+    pub fn mark_all_dirty(&mut self) {
+        self.all_dirty = true;
+        self.regions.clear();
+    }
+
+    /// Returns true if the whole console must be redrawn.
+    pub fn is_all_dirty(&self) -> bool {
+        self.all_dirty
+    }
+
+    /// Returns true if anything must be redrawn.
+    pub fn is_any_dirty(&self) -> bool {
+        self.all_dirty || !self.regions.is_empty()
+    }
+
+    /// Returns true if any cell of the given region must be redrawn.
+    pub fn is_dirty(&self, rect: &CellRect) -> bool {
+        self.all_dirty || self.regions.iter().any(|r| r.intersects(rect))
+    }
+
+    /// Returns true if the given cell must be redrawn.
+    pub fn is_cell_dirty(&self, x: i32, y: i32) -> bool {
+        self.all_dirty || self.regions.iter().any(|r| r.contains(x, y))
+    }
+
+    /// Returns the regions marked dirty since the last render. Empty when the whole console
+    /// is dirty, see [`is_all_dirty`](Self::is_all_dirty).
+    pub fn dirty_regions(&self) -> &[CellRect] {
+        &self.regions
+    }
+
+    /// Returns true if the content of the cell changed during the last render. Always false
+    /// unless [change tracking](Self::with_change_tracking) is enabled.
+    pub fn is_cell_changed(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.size.0 as i32 || y >= self.size.1 as i32 {
+            return false;
+        }
+        self.changed[x as usize + y as usize * self.size.0 as usize]
+    }
+
+    /// Returns the smallest rectangle containing every cell that changed during the last
+    /// render, or `None` if nothing changed or [change tracking](Self::with_change_tracking)
+    /// is disabled.
+    pub fn changed_bounds(&self) -> Option<CellRect> {
+        self.changed_bounds
+    }
+
+    /// Remembers a region drawn over by the compositor, to redraw during the next render.
+    pub(crate) fn mark_composited(&mut self, rect: CellRect) {
+        self.composited.push(rect);
+    }
+
+    /// Marks the regions drawn over by the compositor during the previous render dirty, and
+    /// clears them on the console so they are not blended again.
+    pub(crate) fn clear_composited(&mut self, console: &mut Console) {
+        for index in 0..self.composited.len() {
+            let rect = self.composited[index];
+            self.mark_dirty(rect);
+            console.area(
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                Some((255, 255, 255, 255)),
+                Some((0, 0, 0, 255)),
+                Some(' ' as u16),
+            );
+        }
+        self.composited.clear();
+    }
+
+    /// Compares the console with its content at the end of the previous render when
+    /// tracking changes, then clears the dirty regions.
+    pub(crate) fn end_render(&mut self, console: &Console) {
+        if self.track_changes {
+            self.track_changed_cells(console);
+        }
+
+        self.all_dirty = false;
+        self.regions.clear();
+    }
+
+    fn track_changed_cells(&mut self, console: &Console) {
+        let size = (console.get_width(), console.get_height());
+        let ascii = console.borrow_ascii();
+        let fore = console.borrow_foreground();
+        let back = console.borrow_background();
+
+        if size != self.size {
+            self.size = size;
+            self.ascii = ascii.to_vec();
+            self.fore = fore.to_vec();
+            self.back = back.to_vec();
+            self.changed = vec![true; ascii.len()];
+            self.changed_bounds = Some(CellRect::new(0, 0, size.0, size.1));
+        } else {
+            self.changed_bounds = None;
+            for index in 0..self.changed.len() {
+                let changed = self.ascii[index] != ascii[index]
+                    || self.fore[index] != fore[index]
+                    || self.back[index] != back[index];
+                self.changed[index] = changed;
+                if changed {
+                    let x = (index % size.0 as usize) as i32;
+                    let y = (index / size.0 as usize) as i32;
+                    let cell = CellRect::cell(x, y);
+                    self.changed_bounds = Some(match self.changed_bounds {
+                        Some(bounds) => bounds.union(&cell),
+                        None => cell,
+                    });
+                }
+            }
+            self.ascii.copy_from_slice(ascii);
+            self.fore.copy_from_slice(fore);
+            self.back.copy_from_slice(back);
+        }
+    }
+}
+
+/// Run criteria running a render system only when some region of the
+/// [`RootConsole`](crate::RootConsole) is dirty.
+pub fn root_console_dirty(dirty_regions: Res<DirtyRegions>) -> ShouldRun {
+    if dirty_regions.is_any_dirty() {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dirty_until_the_end_of_the_render() {
+        let mut dirty_regions = DirtyRegions::default();
+        let console = Console::new(10, 5);
+        assert!(dirty_regions.is_all_dirty());
+
+        dirty_regions.end_render(&console);
+        assert!(!dirty_regions.is_any_dirty());
+
+        dirty_regions.mark_dirty(CellRect::new(2, 1, 3, 2));
+        assert!(dirty_regions.is_cell_dirty(4, 2));
+        assert!(!dirty_regions.is_cell_dirty(5, 2));
+        assert!(dirty_regions.is_dirty(&CellRect::new(0, 0, 3, 2)));
+        assert!(!dirty_regions.is_dirty(&CellRect::new(0, 0, 2, 5)));
+
+        dirty_regions.end_render(&console);
+        assert!(!dirty_regions.is_any_dirty());
+    }
+
+    #[test]
+    fn changes_are_not_tracked_by_default() {
+        let mut dirty_regions = DirtyRegions::default();
+        let mut console = Console::new(10, 5);
+
+        dirty_regions.end_render(&console);
+        console.back(3, 3, (255, 0, 0, 255));
+        dirty_regions.end_render(&console);
+
+        assert!(!dirty_regions.is_tracking_changes());
+        assert!(!dirty_regions.is_cell_changed(3, 3));
+        assert_eq!(dirty_regions.changed_bounds(), None);
+    }
+
+    #[test]
+    fn tracks_the_changed_cells() {
+        let mut dirty_regions = DirtyRegions::default().with_change_tracking();
+        let mut console = Console::new(10, 5);
+
+        dirty_regions.end_render(&console);
+        assert!(dirty_regions.is_cell_changed(9, 4));
+        assert_eq!(
+            dirty_regions.changed_bounds(),
+            Some(CellRect::new(0, 0, 10, 5))
+        );
+
+        dirty_regions.end_render(&console);
+        assert!(!dirty_regions.is_cell_changed(9, 4));
+        assert_eq!(dirty_regions.changed_bounds(), None);
+
+        console.back(3, 3, (255, 0, 0, 255));
+        console.ascii(6, 1, '@' as u16);
+        dirty_regions.end_render(&console);
+        assert!(dirty_regions.is_cell_changed(3, 3));
+        assert!(!dirty_regions.is_cell_changed(4, 3));
+        assert_eq!(
+            dirty_regions.changed_bounds(),
+            Some(CellRect::new(3, 1, 4, 3))
+        );
+
+        dirty_regions.set_change_tracking(false);
+        console.back(0, 0, (255, 0, 0, 255));
+        dirty_regions.end_render(&console);
+        assert!(!dirty_regions.is_cell_changed(0, 0));
+        assert_eq!(dirty_regions.changed_bounds(), None);
+    }
+}
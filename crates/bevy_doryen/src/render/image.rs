@@ -1,18 +1,35 @@
+use bevy::reflect::TypeUuid;
 use doryen_rs::{Color, Console, Image};
 
-/// Wrapper type around `Image` that allows for asset path enhancements automatically.
+/// Wrapper type around `Image`, usable as a Bevy asset.
 ///
-/// An easy way to load PNG images and blit them on the console
+/// An easy way to load PNG images and blit them on the console. Load them with the
+/// [`AssetServer`](bevy::asset::AssetServer), from its asset folder:
+/// `asset_server.load::<DoryenImage, _>("skull.png")`, then get them from the
+/// [`Assets<DoryenImage>`](bevy::asset::Assets) resource once loaded.
+#[derive(TypeUuid)]
+#[uuid = "be703c38-04c7-4d7c-a7ab-88fbf0e9fb19"]
 pub struct DoryenImage(pub Image);
 
 impl DoryenImage {
-    /// Create an image and load a PNG file.
+    /// Create an image and load a PNG file from the `assets` folder of the working directory.
     /// On the web platform, image loading is asynchronous.
     /// Using blit methods before the image is loaded has no impact on the console.
+    #[deprecated(note = "load the image with the `AssetServer` to honor its asset folder")]
     pub fn new(file_path: &str) -> Self {
         Self(Image::new(&format!("assets/{file_path}")))
     }
 
+    /// Create an image from decoded RGBA pixels.
+    pub fn from_rgba(rgba: &image::RgbaImage) -> Self {
+        let mut image = Image::new_empty(rgba.width(), rgba.height());
+        for (x, y, pixel) in rgba.enumerate_pixels() {
+            let [r, g, b, a] = pixel.0;
+            image.put_pixel(x, y, (r, g, b, a));
+        }
+        Self(image)
+    }
+
     /// Create an empty image.
     pub fn new_empty(width: u32, height: u32) -> Self {
         Self(Image::new_empty(width, height))
@@ -0,0 +1,306 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::{Component, Entity, Local, Query, ResMut};
+use doryen_rs::{Color, Console};
+
+use crate::{CellRect, DirtyRegions, RootConsole};
+
+/// An offscreen console, blitted onto the [`RootConsole`] during the
+/// [`DoryenRenderStage::PostRender`](crate::DoryenRenderStage::PostRender) stage.
+///
+/// The consoles are blitted in increasing [`z`](Self::z) order, so the console with the
+/// highest `z` ends up on top. Draw on them like on any [`Console`], through `Deref`.
+///
+/// The cells covered by the consoles are marked in the [`DirtyRegions`] and cleared at the
+/// start of the next render: render systems redrawing the dirty regions draw beneath the
+/// consoles again, so translucent consoles do not build up and moved consoles do not leave a
+/// trail.
+#[derive(Component)]
+pub struct DoryenConsole {
+    /// The console to blit.
+    pub console: Console,
+
+    /// The position of the top left corner of the console on the [`RootConsole`].
+    pub position: (i32, i32),
+
+    /// The blit order of the console. Consoles with a higher `z` are blitted on top.
+    pub z: i32,
+
+    /// The opacity of the foreground, from 0.0 (invisible) to 1.0 (opaque).
+    pub fore_alpha: f32,
+
+    /// The opacity of the background, from 0.0 (invisible) to 1.0 (opaque).
+    pub back_alpha: f32,
+
+    /// The cells having this background color are not blitted.
+    pub key_color: Option<Color>,
+}
+
+impl DoryenConsole {
+    /// Create an opaque console at the top left corner of the [`RootConsole`].
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            console: Console::new(width, height),
+            position: (0, 0),
+            z: 0,
+            fore_alpha: 1.0,
+            back_alpha: 1.0,
+            key_color: None,
+        }
+    }
+
+    /// Set the position of the top left corner of the console on the [`RootConsole`].
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = (x, y);
+        self
+    }
+
+    /// Set the blit order of the console.
+    pub fn with_z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set the opacity of both the foreground and the background.
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.set_alpha(alpha);
+        self
+    }
+
+    /// Set the background color of the cells that are not blitted.
+    pub fn with_key_color(mut self, key_color: Color) -> Self {
+        self.key_color = Some(key_color);
+        self
+    }
+
+    /// Set the opacity of both the foreground and the background.
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.fore_alpha = alpha;
+        self.back_alpha = alpha;
+    }
+}
+
+impl Deref for DoryenConsole {
+    type Target = Console;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.console
+    }
+}
+
+impl DerefMut for DoryenConsole {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.console
+    }
+}
+
+/// Blits every [`DoryenConsole`] onto the [`RootConsole`], in `z` order.
+///
+/// The blit order is kept from frame to frame: sorting the order of the previous frame again
+/// only takes a single pass, and consoles with the same `z` keep their relative order.
+pub(crate) fn blit_doryen_consoles(
+    mut root_console: ResMut<RootConsole>,
+    mut dirty_regions: ResMut<DirtyRegions>,
+    consoles: Query<(Entity, &DoryenConsole)>,
+    mut order: Local<Vec<(i32, Entity)>>,
+) {
+    order.retain_mut(|(z, entity)| match consoles.get(*entity) {
+        Ok((_, console)) => {
+            *z = console.z;
+            true
+        }
+        Err(_) => false,
+    });
+    if order.len() < consoles.iter().len() {
+        for (entity, console) in &consoles {
+            if !order.iter().any(|&(_, known)| known == entity) {
+                order.push((console.z, entity));
+            }
+        }
+    }
+    order.sort_by_key(|&(z, _)| z);
+
+    let root_console = &mut **root_console;
+    for (_, console) in consoles.iter_many(order.iter().map(|&(_, entity)| entity)) {
+        console.console.blit(
+            console.position.0,
+            console.position.1,
+            root_console,
+            console.fore_alpha,
+            console.back_alpha,
+            console.key_color,
+        );
+        dirty_regions.mark_composited(CellRect::new(
+            console.position.0,
+            console.position.1,
+            console.get_width(),
+            console.get_height(),
+        ));
+    }
+}
+
+/// Clears the cells drawn over by the [`DoryenConsole`]s during the previous render, and
+/// marks them dirty.
+pub(crate) fn clear_composited_regions(
+    mut root_console: ResMut<RootConsole>,
+    mut dirty_regions: ResMut<DirtyRegions>,
+) {
+    dirty_regions.clear_composited(&mut root_console);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Mut, Res, Schedule, Stage, SystemStage, World};
+
+    use super::*;
+
+    const BLACK: Color = (0, 0, 0, 255);
+    const RED: Color = (255, 0, 0, 255);
+    const BLUE: Color = (0, 0, 255, 255);
+
+    fn filled(width: u32, height: u32, back: Color) -> DoryenConsole {
+        let mut console = DoryenConsole::new(width, height);
+        console.clear(None, Some(back), None);
+        console
+    }
+
+    fn blit_schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "blit",
+            SystemStage::single_threaded().with_system(blit_doryen_consoles),
+        );
+        schedule
+    }
+
+    fn clear_root(world: &mut World) {
+        world
+            .resource_mut::<RootConsole>()
+            .clear(None, Some(BLACK), None);
+    }
+
+    fn root_back(world: &World) -> Vec<Color> {
+        let root_console = world.resource::<RootConsole>();
+        (0..root_console.get_width() as i32)
+            .map(|x| *root_console.get_back(x, 0).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn blits_in_z_order() {
+        let mut world = World::new();
+        world.insert_resource(RootConsole(Some(Console::new(4, 1))));
+        world.init_resource::<DirtyRegions>();
+        let top = world.spawn(filled(2, 1, RED).with_z(1)).id();
+        let bottom = world
+            .spawn(filled(2, 1, BLUE).with_position(1, 0).with_z(0))
+            .id();
+        let mut schedule = blit_schedule();
+
+        schedule.run(&mut world);
+        assert_eq!(root_back(&world), [RED, RED, BLUE, BLACK]);
+
+        world.get_mut::<DoryenConsole>(top).unwrap().z = -1;
+        world.spawn(filled(2, 1, RED).with_position(2, 0).with_z(5));
+        clear_root(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(root_back(&world), [RED, BLUE, RED, RED]);
+
+        world.despawn(bottom);
+        world.get_mut::<DoryenConsole>(top).unwrap().position = (1, 0);
+        clear_root(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(root_back(&world), [BLACK, RED, RED, RED]);
+    }
+
+    #[test]
+    fn blends_with_alpha_and_skips_the_key_color() {
+        let mut world = World::new();
+        world.insert_resource(RootConsole(Some(Console::new(3, 1))));
+        world.init_resource::<DirtyRegions>();
+        let mut console = DoryenConsole::new(3, 1)
+            .with_key_color(BLUE)
+            .with_alpha(0.5);
+        console.back(0, 0, (200, 100, 0, 255));
+        console.back(1, 0, BLUE);
+        console.back(2, 0, (200, 100, 0, 0));
+        world.spawn(console);
+
+        blit_schedule().run(&mut world);
+        let back = root_back(&world);
+
+        let (r, g, b, _) = back[0];
+        assert!((99..=101).contains(&r), "{:?}", back[0]);
+        assert!((49..=51).contains(&g), "{:?}", back[0]);
+        assert_eq!(b, 0);
+        assert_eq!(back[1], BLACK);
+        assert_eq!((back[2].0, back[2].1, back[2].2), (0, 0, 0));
+    }
+
+    const GRAY: Color = (100, 100, 100, 255);
+
+    /// Redraws the dirty cells of the root console in gray.
+    fn render_dirty(mut root_console: ResMut<RootConsole>, dirty_regions: Res<DirtyRegions>) {
+        for x in 0..root_console.get_width() as i32 {
+            if dirty_regions.is_cell_dirty(x, 0) {
+                root_console.back(x, 0, GRAY);
+            }
+        }
+    }
+
+    fn render_schedule() -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule
+            .add_stage(
+                "first",
+                SystemStage::single_threaded().with_system(clear_composited_regions),
+            )
+            .add_stage(
+                "render",
+                SystemStage::single_threaded().with_system(render_dirty),
+            )
+            .add_stage(
+                "post_render",
+                SystemStage::single_threaded().with_system(blit_doryen_consoles),
+            );
+        schedule
+    }
+
+    fn render(world: &mut World, schedule: &mut Schedule) -> Vec<Color> {
+        schedule.run(world);
+        world.resource_scope(|world, mut dirty_regions: Mut<DirtyRegions>| {
+            dirty_regions.end_render(world.resource::<RootConsole>());
+        });
+        root_back(world)
+    }
+
+    #[test]
+    fn redraws_beneath_the_consoles() {
+        let mut world = World::new();
+        world.insert_resource(RootConsole(Some(Console::new(4, 1))));
+        world.init_resource::<DirtyRegions>();
+        let console = world
+            .spawn(filled(1, 1, RED).with_position(1, 0).with_alpha(0.5))
+            .id();
+        let mut schedule = render_schedule();
+
+        let first = render(&mut world, &mut schedule);
+        assert_eq!(first[0], GRAY);
+        assert_ne!(first[1], GRAY);
+        // The translucent console does not build up over the frames.
+        assert_eq!(render(&mut world, &mut schedule), first);
+        assert_eq!(render(&mut world, &mut schedule), first);
+
+        // Nor does it leave a trail when moved.
+        world.get_mut::<DoryenConsole>(console).unwrap().position = (2, 0);
+        assert_eq!(
+            render(&mut world, &mut schedule),
+            [GRAY, GRAY, first[1], GRAY]
+        );
+
+        world.despawn(console);
+        assert_eq!(render(&mut world, &mut schedule), [GRAY; 4]);
+    }
+}
@@ -0,0 +1,460 @@
+//! Recording and replay of the `doryen-rs` input.
+
+use std::{fmt, path::Path, time::Duration};
+
+use bevy::{prelude::Resource, utils::Instant};
+use brltk_common::names;
+use doryen_rs::{MouseButton, ScanCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{doryen::InputApi, MouseButtonNames, ScanCodeNames};
+
+/// The input reported by Doryen during a single frame.
+///
+/// Empty fields are left out of the saved recordings, so a frame without input only takes
+/// a few bytes. Keys and mouse buttons are saved by name, with [`ScanCodeNames`] and
+/// [`MouseButtonNames`].
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputFrame {
+    /// The time elapsed since the previous frame, saved as microseconds. Bevy's
+    /// [`Time`](bevy::time::Time) advances by this much while recording and replaying.
+    #[serde(default, with = "micros")]
+    pub delta: Duration,
+
+    /// Characters typed during the frame.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+
+    /// Whether the window close button has been activated.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub close_requested: bool,
+
+    /// The keys pressed during the frame.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "names::serialize::<ScanCodeNames, _>",
+        deserialize_with = "names::deserialize::<ScanCodeNames, _>"
+    )]
+    pub keys_pressed: Vec<ScanCode>,
+
+    /// The keys released during the frame.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "names::serialize::<ScanCodeNames, _>",
+        deserialize_with = "names::deserialize::<ScanCodeNames, _>"
+    )]
+    pub keys_released: Vec<ScanCode>,
+
+    /// The mouse position in console cells coordinates. `None` when the mouse did not
+    /// move since the previous frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_position: Option<(f32, f32)>,
+
+    /// The mouse buttons pressed during the frame.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "names::serialize::<MouseButtonNames, _>",
+        deserialize_with = "names::deserialize::<MouseButtonNames, _>"
+    )]
+    pub mouse_buttons_pressed: Vec<MouseButton>,
+
+    /// The mouse buttons released during the frame.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "names::serialize::<MouseButtonNames, _>",
+        deserialize_with = "names::deserialize::<MouseButtonNames, _>"
+    )]
+    pub mouse_buttons_released: Vec<MouseButton>,
+}
+
+impl InputFrame {
+    /// Reads the input of the current frame from Doryen.
+    pub(crate) fn capture(
+        mouse_button_listeners: &[MouseButton],
+        input: &mut dyn InputApi,
+        delta: Duration,
+    ) -> Self {
+        Self {
+            delta,
+            text: input.text(),
+            close_requested: input.close_requested(),
+            keys_pressed: input.keys_pressed().copied().collect(),
+            keys_released: input.keys_released().copied().collect(),
+            mouse_position: Some(input.mouse_pos()),
+            mouse_buttons_pressed: mouse_button_listeners
+                .iter()
+                .copied()
+                .filter(|&b| input.mouse_button_pressed(b))
+                .collect(),
+            mouse_buttons_released: mouse_button_listeners
+                .iter()
+                .copied()
+                .filter(|&b| input.mouse_button_released(b))
+                .collect(),
+        }
+    }
+}
+
+mod micros {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_micros() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_micros)
+    }
+}
+
+/// A sequence of recorded [`InputFrame`]s, saved as RON.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// The recorded frames, in order.
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    /// Parse a recording from a RON string.
+    pub fn from_ron_str(ron: &str) -> Result<Self, InputRecordingError> {
+        ron::from_str(ron).map_err(InputRecordingError::Parse)
+    }
+
+    /// Serialize the recording to a RON string, one frame per line.
+    pub fn to_ron_string(&self) -> Result<String, InputRecordingError> {
+        let mut ron = String::from("(frames:[\n");
+        for frame in &self.frames {
+            ron.push_str(&ron::to_string(frame).map_err(InputRecordingError::Serialize)?);
+            ron.push_str(",\n");
+        }
+        ron.push_str("])\n");
+        Ok(ron)
+    }
+
+    /// Load a recording from a RON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputRecordingError> {
+        Self::from_ron_str(&std::fs::read_to_string(path).map_err(InputRecordingError::Io)?)
+    }
+
+    /// Save the recording to a RON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputRecordingError> {
+        std::fs::write(path, self.to_ron_string()?).map_err(InputRecordingError::Io)
+    }
+}
+
+/// An error raised while loading or saving an [`InputRecording`].
+#[derive(Debug)]
+pub enum InputRecordingError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The file is not a valid RON input recording.
+    Parse(ron::error::SpannedError),
+    /// The recording could not be serialized.
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for InputRecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "input recording i/o error: {e}"),
+            Self::Parse(e) => write!(f, "invalid input recording: {e}"),
+            Self::Serialize(e) => write!(f, "cannot serialize input recording: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InputRecordingError {}
+
+/// Records the Doryen input, or replays a recording in place of the live input.
+///
+/// Replayed frames go through the same handling as the live input, including the key
+/// repeat and the mouse events, so a session recorded from the start of the app is
+/// reproduced exactly. While recording or replaying, Bevy's [`Time`](bevy::time::Time) is
+/// pinned to the time of the frames, for the apps running the
+/// [`TimePlugin`](bevy::time::TimePlugin). Insert [`InputRecorder::recording`] or
+/// [`InputRecorder::replaying`] before running the app to cover the very first frame.
+#[derive(Default, Debug, Resource)]
+pub struct InputRecorder {
+    state: RecorderState,
+    replay_started: bool,
+}
+
+#[derive(Default, Debug)]
+enum RecorderState {
+    #[default]
+    Idle,
+    Recording {
+        recording: InputRecording,
+        last_frame: Option<Instant>,
+        mouse_position: Option<(f32, f32)>,
+    },
+    Replaying {
+        recording: InputRecording,
+        last_frame: Option<Instant>,
+        next_frame: usize,
+    },
+}
+
+impl InputRecorder {
+    /// Create a recorder recording from the first frame.
+    pub fn recording() -> Self {
+        let mut recorder = Self::default();
+        recorder.start_recording();
+        recorder
+    }
+
+    /// Create a recorder replaying the given recording from the first frame.
+    pub fn replaying(recording: InputRecording) -> Self {
+        let mut recorder = Self::default();
+        recorder.replay(recording);
+        recorder
+    }
+
+    /// Start a new recording, dropping the current recording or replay.
+    pub fn start_recording(&mut self) {
+        self.state = RecorderState::Recording {
+            recording: InputRecording::default(),
+            last_frame: None,
+            mouse_position: None,
+        };
+    }
+
+    /// Stop recording and return the recorded frames. Returns `None` when not recording.
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        match std::mem::take(&mut self.state) {
+            RecorderState::Recording { recording, .. } => Some(recording),
+            state => {
+                self.state = state;
+                None
+            }
+        }
+    }
+
+    /// Replay the given recording in place of the live input, starting next frame.
+    /// The live input comes back once every frame has been replayed.
+    pub fn replay(&mut self, recording: InputRecording) {
+        self.state = RecorderState::Replaying {
+            recording,
+            last_frame: None,
+            next_frame: 0,
+        };
+        self.replay_started = true;
+    }
+
+    /// Stop the current replay and go back to the live input.
+    pub fn stop_replay(&mut self) {
+        if self.is_replaying() {
+            self.state = RecorderState::Idle;
+        }
+    }
+
+    /// Returns true while recording.
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, RecorderState::Recording { .. })
+    }
+
+    /// Returns true while a recording is being replayed.
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.state, RecorderState::Replaying { .. })
+    }
+
+    /// Returns true, once, when a replay begins, so the input state can be reset.
+    pub(crate) fn take_replay_started(&mut self) -> bool {
+        std::mem::take(&mut self.replay_started)
+    }
+
+    /// Returns the input of this frame, recorded from or replacing the live input, and the
+    /// time of the frame.
+    pub(crate) fn next_frame(
+        &mut self,
+        mouse_button_listeners: &[MouseButton],
+        input: &mut dyn InputApi,
+    ) -> (InputFrame, Instant) {
+        self.next_frame_at(Instant::now(), |delta| {
+            InputFrame::capture(mouse_button_listeners, input, delta)
+        })
+    }
+
+    fn next_frame_at(
+        &mut self,
+        now: Instant,
+        capture: impl FnOnce(Duration) -> InputFrame,
+    ) -> (InputFrame, Instant) {
+        if let RecorderState::Replaying {
+            recording,
+            last_frame,
+            next_frame,
+        } = &mut self.state
+        {
+            if let Some(frame) = recording.frames.get(*next_frame) {
+                *next_frame += 1;
+                let time = last_frame.map_or(now, |last_frame| last_frame + frame.delta);
+                *last_frame = Some(time);
+                return (frame.clone(), time);
+            }
+            self.state = RecorderState::Idle;
+        }
+
+        if let RecorderState::Recording {
+            recording,
+            last_frame,
+            mouse_position,
+        } = &mut self.state
+        {
+            let delta = last_frame.map_or(Duration::ZERO, |last_frame| {
+                now.saturating_duration_since(last_frame)
+            });
+            *last_frame = Some(now);
+            let frame = capture(delta);
+
+            let mut recorded = frame.clone();
+            if recorded.mouse_position == *mouse_position {
+                recorded.mouse_position = None;
+            } else {
+                *mouse_position = recorded.mouse_position;
+            }
+            recording.frames.push(recorded);
+
+            return (frame, now);
+        }
+
+        (capture(Duration::ZERO), now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DoryenInput;
+
+    use super::*;
+
+    fn live_frames() -> Vec<InputFrame> {
+        vec![
+            InputFrame {
+                mouse_position: Some((1.5, 2.5)),
+                ..Default::default()
+            },
+            InputFrame {
+                text: "a".to_string(),
+                keys_pressed: vec![ScanCode::A, ScanCode::LeftShift],
+                mouse_position: Some((1.5, 2.5)),
+                ..Default::default()
+            },
+            InputFrame {
+                keys_released: vec![ScanCode::A],
+                mouse_position: Some((4.25, 2.5)),
+                mouse_buttons_pressed: vec![MouseButton::Left],
+                ..Default::default()
+            },
+            InputFrame {
+                close_requested: true,
+                mouse_position: Some((4.25, 2.5)),
+                mouse_buttons_released: vec![MouseButton::Left],
+                ..Default::default()
+            },
+        ]
+    }
+
+    const FRAME_TIMES: [u64; 4] = [0, 16, 50, 66];
+
+    #[test]
+    fn record_then_replay() {
+        let start = Instant::now();
+        let mut recorder = InputRecorder::recording();
+        let mut live_input = DoryenInput::default();
+        let mut live = Vec::new();
+        for (frame, millis) in live_frames().into_iter().zip(FRAME_TIMES) {
+            let (frame, now) =
+                recorder.next_frame_at(start + Duration::from_millis(millis), |delta| InputFrame {
+                    delta,
+                    ..frame
+                });
+            live_input.handle_input(&frame, None, now);
+            live.push((
+                live_input.mouse_position(),
+                live_input.key(ScanCode::A),
+                now,
+            ));
+        }
+
+        let ron = recorder.stop_recording().unwrap().to_ron_string().unwrap();
+        let recording = InputRecording::from_ron_str(&ron).unwrap();
+        assert_eq!(recording.frames.len(), 4);
+        assert_eq!(recording.frames[1].mouse_position, None);
+        assert_eq!(
+            recording.frames[2].delta,
+            Duration::from_millis(FRAME_TIMES[2] - FRAME_TIMES[1])
+        );
+        assert_eq!(recording.to_ron_string().unwrap(), ron);
+
+        let replay_start = start + Duration::from_secs(10);
+        let mut recorder = InputRecorder::replaying(recording.clone());
+        assert!(recorder.take_replay_started());
+        let mut replayed_input = DoryenInput::default();
+        for (index, (mouse_position, a_down, now)) in live.into_iter().enumerate() {
+            let (frame, time) = recorder
+                .next_frame_at(replay_start + Duration::from_secs(index as u64), |_| {
+                    panic!("the live input is read during a replay")
+                });
+            assert_eq!(frame, recording.frames[index]);
+            assert_eq!(time - replay_start, now - start);
+            replayed_input.handle_input(&frame, None, time);
+            assert_eq!(replayed_input.mouse_position(), mouse_position);
+            assert_eq!(replayed_input.key(ScanCode::A), a_down);
+            assert_eq!(replayed_input.close_requested(), index == 3);
+        }
+
+        assert!(recorder.is_replaying());
+        recorder.next_frame_at(replay_start, |_| InputFrame::default());
+        assert!(!recorder.is_replaying());
+    }
+
+    #[test]
+    fn input_names() {
+        let frame = InputFrame {
+            delta: Duration::from_micros(16_667),
+            keys_pressed: vec![ScanCode::Key1, ScanCode::Escape],
+            mouse_buttons_released: vec![MouseButton::Right],
+            ..Default::default()
+        };
+
+        let ron = ron::to_string(&frame).unwrap();
+        assert_eq!(
+            ron,
+            r#"(delta:16667,keys_pressed:["Key1","Escape"],mouse_buttons_released:["Right"])"#
+        );
+        assert_eq!(ron::from_str::<InputFrame>(&ron).unwrap(), frame);
+        assert!(ron::from_str::<InputFrame>(r#"(keys_pressed:["Hyper"])"#).is_err());
+    }
+
+    #[test]
+    fn records_every_key() {
+        let keys = vec![
+            ScanCode::Kp8,
+            ScanCode::Period,
+            ScanCode::Comma,
+            ScanCode::Slash,
+            ScanCode::Minus,
+            ScanCode::Equal,
+            ScanCode::LeftBracket,
+            ScanCode::Semicolon,
+            ScanCode::Unknown,
+        ];
+        let mut recorder = InputRecorder::recording();
+        recorder.next_frame_at(Instant::now(), |delta| InputFrame {
+            delta,
+            keys_pressed: keys.clone(),
+            ..Default::default()
+        });
+
+        let ron = recorder.stop_recording().unwrap().to_ron_string().unwrap();
+        let recording = InputRecording::from_ron_str(&ron).unwrap();
+        assert_eq!(recording.frames[0].keys_pressed, keys);
+    }
+}
@@ -0,0 +1,823 @@
+//! Mapping of raw input to game actions.
+
+use std::{
+    borrow::Cow, collections::BTreeMap, fmt, hash::Hash, marker::PhantomData, path::Path,
+    time::Duration,
+};
+
+use bevy::{
+    prelude::{
+        App, CoreStage, IntoSystemDescriptor, KeyCode, MouseButton, Plugin, Res, ResMut, Resource,
+        SystemLabel, Time,
+    },
+    utils::{HashMap, Instant},
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{names, ConsoleInput, ConsoleInputSystem, Names, SerdeNames};
+
+/// The state of the modifier keys.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Modifiers {
+    /// Either shift key is down.
+    #[serde(default)]
+    pub shift: bool,
+    /// Either control key is down.
+    #[serde(default)]
+    pub ctrl: bool,
+    /// Either alt key is down.
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl Modifiers {
+    /// No modifier.
+    pub const NONE: Self = Self {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+
+    /// Only shift.
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+
+    /// Only control.
+    pub const CTRL: Self = Self {
+        ctrl: true,
+        ..Self::NONE
+    };
+
+    /// Only alt.
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
+
+    /// Returns true if every modifier of `other` is also down in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        (self.shift || !other.shift) && (self.ctrl || !other.ctrl) && (self.alt || !other.alt)
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self {
+            shift: self.shift || other.shift,
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+        }
+    }
+
+    fn without(self, other: Self) -> Self {
+        Self {
+            shift: self.shift && !other.shift,
+            ctrl: self.ctrl && !other.ctrl,
+            alt: self.alt && !other.alt,
+        }
+    }
+}
+
+/// An input resource that actions can be read from.
+pub trait ActionInput: Resource {
+    /// The key type of the input.
+    type Key: Copy + Eq + Hash + Send + Sync + 'static;
+    /// The mouse button type of the input.
+    type MouseButton: Copy + Eq + Hash + Send + Sync + 'static;
+    /// The names of the keys, saved in the [`InputMap`] files.
+    type KeyNames: Names<Value = Self::Key>;
+    /// The names of the mouse buttons, saved in the [`InputMap`] files.
+    type MouseButtonNames: Names<Value = Self::MouseButton>;
+
+    /// Returns the modifier set while the given key is held, [`Modifiers::NONE`] for the keys
+    /// other than the modifier keys.
+    fn key_modifiers(key: Self::Key) -> Modifiers;
+
+    /// Returns true if the given key is currently pressed.
+    fn key_down(&self, key: Self::Key) -> bool;
+
+    /// Returns true if the given mouse button is currently pressed.
+    fn mouse_button_down(&self, mouse_button: Self::MouseButton) -> bool;
+
+    /// Returns the modifier keys currently pressed.
+    fn modifiers(&self) -> Modifiers;
+}
+
+impl ActionInput for ConsoleInput {
+    type Key = KeyCode;
+    type MouseButton = MouseButton;
+    type KeyNames = SerdeNames<KeyCode>;
+    type MouseButtonNames = SerdeNames<MouseButton>;
+
+    fn key_modifiers(key: KeyCode) -> Modifiers {
+        match key {
+            KeyCode::LShift | KeyCode::RShift => Modifiers::SHIFT,
+            KeyCode::LControl | KeyCode::RControl => Modifiers::CTRL,
+            KeyCode::LAlt | KeyCode::RAlt => Modifiers::ALT,
+            _ => Modifiers::NONE,
+        }
+    }
+
+    fn key_down(&self, key: KeyCode) -> bool {
+        self.key(key)
+    }
+
+    fn mouse_button_down(&self, mouse_button: MouseButton) -> bool {
+        self.mouse_button(mouse_button)
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            shift: self.key(KeyCode::LShift) || self.key(KeyCode::RShift),
+            ctrl: self.key(KeyCode::LControl) || self.key(KeyCode::RControl),
+            alt: self.key(KeyCode::LAlt) || self.key(KeyCode::RAlt),
+        }
+    }
+}
+
+/// A single key or mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trigger<K, M> {
+    /// A keyboard key.
+    Key(K),
+    /// A mouse button.
+    Mouse(M),
+}
+
+/// Delay and interval of a binding repeating while held. Both are saved as milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Repeat {
+    /// The time the binding must be held before it starts repeating.
+    #[serde(with = "millis")]
+    pub delay: Duration,
+    /// The time between two repeats.
+    #[serde(with = "millis")]
+    pub interval: Duration,
+}
+
+mod millis {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(400),
+            interval: Duration::from_millis(80),
+        }
+    }
+}
+
+/// A combination of triggers activating an action.
+///
+/// All the triggers must be held at the same time (a chord), along with exactly the given
+/// modifiers: a binding to `Up` is not active while `Shift+Up` is held. The modifier keys
+/// among the triggers are not extra modifiers, so a binding to `LShift` alone or to the
+/// chord `LControl` and `S` is active while its keys are held.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Binding<K, M> {
+    /// The keys and mouse buttons that must all be held.
+    pub triggers: Vec<Trigger<K, M>>,
+    /// The modifiers that must be held, and no other.
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    /// Whether the action is pressed again while the binding is held.
+    #[serde(default)]
+    pub repeat: Option<Repeat>,
+}
+
+impl<K, M> Binding<K, M> {
+    /// A binding to a single key.
+    pub fn key(key: K) -> Self {
+        Self::chord([Trigger::Key(key)])
+    }
+
+    /// A binding to a single mouse button.
+    pub fn mouse(mouse_button: M) -> Self {
+        Self::chord([Trigger::Mouse(mouse_button)])
+    }
+
+    /// A binding to several triggers held together.
+    pub fn chord(triggers: impl IntoIterator<Item = Trigger<K, M>>) -> Self {
+        Self {
+            triggers: triggers.into_iter().collect(),
+            modifiers: Modifiers::NONE,
+            repeat: None,
+        }
+    }
+
+    /// Require the given modifiers.
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Repeat the action while the binding is held.
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = Some(repeat);
+        self
+    }
+
+    fn try_map<K2, M2, E>(
+        &self,
+        key: impl Fn(&K) -> Result<K2, E>,
+        mouse_button: impl Fn(&M) -> Result<M2, E>,
+    ) -> Result<Binding<K2, M2>, E> {
+        let triggers = self
+            .triggers
+            .iter()
+            .map(|trigger| match trigger {
+                Trigger::Key(k) => key(k).map(Trigger::Key),
+                Trigger::Mouse(m) => mouse_button(m).map(Trigger::Mouse),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Binding {
+            triggers,
+            modifiers: self.modifiers,
+            repeat: self.repeat,
+        })
+    }
+}
+
+/// The bindings of every action of type `A`, for the input `I`.
+///
+/// Can be loaded from and saved to RON files, which is why actions must be [`Ord`]: it keeps
+/// the saved files stable. The keys and mouse buttons are saved by name, with the
+/// [`KeyNames`](ActionInput::KeyNames) and [`MouseButtonNames`](ActionInput::MouseButtonNames)
+/// of the input.
+#[derive(Resource)]
+pub struct InputMap<A: Ord, I: ActionInput = ConsoleInput> {
+    bindings: BTreeMap<A, Vec<Binding<I::Key, I::MouseButton>>>,
+}
+
+/// An [`InputMap`] as saved, with the keys and mouse buttons by name.
+#[derive(Serialize, Deserialize)]
+struct SavedInputMap<A: Ord, N> {
+    bindings: BTreeMap<A, Vec<Binding<N, N>>>,
+}
+
+impl<A: Ord + Serialize, I: ActionInput> Serialize for InputMap<A, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bindings = BTreeMap::new();
+        for (action, action_bindings) in &self.bindings {
+            let saved = action_bindings
+                .iter()
+                .map(|binding| {
+                    binding.try_map(
+                        |&key| names::name::<I::KeyNames, S::Error>(key),
+                        |&mouse_button| names::name::<I::MouseButtonNames, S::Error>(mouse_button),
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+            bindings.insert(action, saved);
+        }
+        SavedInputMap::<&A, Cow<'static, str>> { bindings }.serialize(serializer)
+    }
+}
+
+impl<'de, A: Ord + Deserialize<'de>, I: ActionInput> Deserialize<'de> for InputMap<A, I> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = SavedInputMap::<A, String>::deserialize(deserializer)?;
+        let mut bindings = BTreeMap::new();
+        for (action, saved_bindings) in saved.bindings {
+            let action_bindings = saved_bindings
+                .iter()
+                .map(|binding| {
+                    binding.try_map(
+                        |name| names::from_name::<I::KeyNames, D::Error>(name),
+                        |name| names::from_name::<I::MouseButtonNames, D::Error>(name),
+                    )
+                })
+                .collect::<Result<_, _>>()?;
+            bindings.insert(action, action_bindings);
+        }
+        Ok(Self { bindings })
+    }
+}
+
+impl<A: Ord, I: ActionInput> Default for InputMap<A, I> {
+    fn default() -> Self {
+        Self {
+            bindings: BTreeMap::new(),
+        }
+    }
+}
+
+impl<A: Ord + Clone, I: ActionInput> Clone for InputMap<A, I> {
+    fn clone(&self) -> Self {
+        Self {
+            bindings: self.bindings.clone(),
+        }
+    }
+}
+
+impl<A: Ord + fmt::Debug, I: ActionInput> fmt::Debug for InputMap<A, I>
+where
+    I::Key: fmt::Debug,
+    I::MouseButton: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputMap")
+            .field("bindings", &self.bindings)
+            .finish()
+    }
+}
+
+impl<A: Ord, I: ActionInput> InputMap<A, I> {
+    /// Add a binding to the given action.
+    pub fn bind(&mut self, action: A, binding: Binding<I::Key, I::MouseButton>) -> &mut Self {
+        self.bindings.entry(action).or_default().push(binding);
+        self
+    }
+
+    /// Add a binding to the given action.
+    pub fn with_binding(mut self, action: A, binding: Binding<I::Key, I::MouseButton>) -> Self {
+        self.bind(action, binding);
+        self
+    }
+
+    /// Remove every binding of the given action.
+    pub fn clear_action(&mut self, action: &A) {
+        self.bindings.remove(action);
+    }
+
+    /// Returns the bindings of the given action.
+    pub fn bindings(&self, action: &A) -> &[Binding<I::Key, I::MouseButton>] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over all the actions and their bindings.
+    pub fn iter(&self) -> impl Iterator<Item = (&A, &[Binding<I::Key, I::MouseButton>])> {
+        self.bindings
+            .iter()
+            .map(|(action, bindings)| (action, bindings.as_slice()))
+    }
+}
+
+impl<A, I> InputMap<A, I>
+where
+    A: Ord + Serialize + DeserializeOwned,
+    I: ActionInput,
+{
+    /// Parse the bindings from a RON string.
+    pub fn from_ron_str(ron: &str) -> Result<Self, InputMapError> {
+        ron::from_str(ron).map_err(InputMapError::Parse)
+    }
+
+    /// Serialize the bindings to a RON string.
+    pub fn to_ron_string(&self) -> Result<String, InputMapError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(InputMapError::Serialize)
+    }
+
+    /// Load the bindings from a RON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputMapError> {
+        Self::from_ron_str(&std::fs::read_to_string(path).map_err(InputMapError::Io)?)
+    }
+
+    /// Save the bindings to a RON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputMapError> {
+        std::fs::write(path, self.to_ron_string()?).map_err(InputMapError::Io)
+    }
+}
+
+/// An error raised while loading or saving an [`InputMap`].
+#[derive(Debug)]
+pub enum InputMapError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The file is not a valid RON input map.
+    Parse(ron::error::SpannedError),
+    /// The input map could not be serialized.
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for InputMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "input map i/o error: {e}"),
+            Self::Parse(e) => write!(f, "invalid input map: {e}"),
+            Self::Serialize(e) => write!(f, "cannot serialize input map: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InputMapError {}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct ActionData {
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+    held_for: Duration,
+    next_repeat: Option<Duration>,
+}
+
+/// The state of every action of type `A`, updated each frame from the [`InputMap`].
+#[derive(Resource)]
+pub struct ActionState<A> {
+    actions: HashMap<A, ActionData>,
+    last_update: Option<Instant>,
+}
+
+impl<A> Default for ActionState<A> {
+    fn default() -> Self {
+        Self {
+            actions: HashMap::default(),
+            last_update: None,
+        }
+    }
+}
+
+impl<A: fmt::Debug> fmt::Debug for ActionState<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionState")
+            .field("actions", &self.actions)
+            .finish()
+    }
+}
+
+impl<A: Eq + Hash> ActionState<A> {
+    /// Returns true if the action is currently held.
+    pub fn pressed(&self, action: &A) -> bool {
+        matches!(self.actions.get(action), Some(data) if data.pressed)
+    }
+
+    /// Returns true if the action was pressed this frame, or repeated while held.
+    pub fn just_pressed(&self, action: &A) -> bool {
+        matches!(self.actions.get(action), Some(data) if data.just_pressed)
+    }
+
+    /// Returns true if the action is not currently held.
+    pub fn released(&self, action: &A) -> bool {
+        !self.pressed(action)
+    }
+
+    /// Returns true if the action was released this frame.
+    pub fn just_released(&self, action: &A) -> bool {
+        matches!(self.actions.get(action), Some(data) if data.just_released)
+    }
+
+    /// Returns for how long the action has been held, or zero if it is not held.
+    pub fn held_for(&self, action: &A) -> Duration {
+        self.actions
+            .get(action)
+            .filter(|data| data.pressed)
+            .map_or(Duration::ZERO, |data| data.held_for)
+    }
+
+    /// Update the state of every action from the current input.
+    pub fn update<I: ActionInput>(&mut self, input_map: &InputMap<A, I>, input: &I, delta: Duration)
+    where
+        A: Ord + Clone,
+    {
+        let modifiers = input.modifiers();
+
+        for (action, bindings) in input_map.iter() {
+            let active = bindings.iter().find(|binding| {
+                // The modifiers set by the keys of the binding do not count as extra modifiers
+                let triggered =
+                    binding
+                        .triggers
+                        .iter()
+                        .fold(Modifiers::NONE, |triggered, trigger| match *trigger {
+                            Trigger::Key(key) => triggered.union(I::key_modifiers(key)),
+                            Trigger::Mouse(_) => triggered,
+                        });
+                !binding.triggers.is_empty()
+                    && modifiers.without(triggered) == binding.modifiers.without(triggered)
+                    && binding.triggers.iter().all(|trigger| match *trigger {
+                        Trigger::Key(key) => input.key_down(key),
+                        Trigger::Mouse(mouse_button) => input.mouse_button_down(mouse_button),
+                    })
+            });
+
+            let data = self.actions.entry(action.clone()).or_default();
+            let was_pressed = data.pressed;
+            data.pressed = active.is_some();
+            data.just_pressed = data.pressed && !was_pressed;
+            data.just_released = !data.pressed && was_pressed;
+
+            match active {
+                Some(binding) if was_pressed => {
+                    data.held_for += delta;
+                    if let (Some(repeat), Some(next_repeat)) = (binding.repeat, data.next_repeat) {
+                        if data.held_for >= next_repeat {
+                            data.just_pressed = true;
+                            data.next_repeat = Some(next_repeat + repeat.interval.max(delta));
+                        }
+                    }
+                }
+                Some(binding) => {
+                    data.held_for = Duration::ZERO;
+                    data.next_repeat = binding.repeat.map(|repeat| repeat.delay);
+                }
+                None => {
+                    data.held_for = Duration::ZERO;
+                    data.next_repeat = None;
+                }
+            }
+        }
+    }
+}
+
+/// The label of the system updating the [`ActionState`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub struct ActionSystem;
+
+/// Adds an [`ActionState<A>`] resource, updated every frame from the [`InputMap<A, I>`]
+/// resource and the input resource `I`.
+///
+/// The input map starts empty, unless one was inserted before adding the plugin.
+pub struct ActionPlugin<A, I = ConsoleInput>(PhantomData<fn() -> (A, I)>);
+
+impl<A, I> Default for ActionPlugin<A, I> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A, I> Plugin for ActionPlugin<A, I>
+where
+    A: Ord + Hash + Clone + Send + Sync + 'static,
+    I: ActionInput,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap<A, I>>()
+            .init_resource::<ActionState<A>>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_action_state::<A, I>
+                    .label(ActionSystem)
+                    .after(ConsoleInputSystem),
+            );
+    }
+}
+
+fn update_action_state<A, I>(
+    time: Option<Res<Time>>,
+    input: Option<Res<I>>,
+    input_map: Res<InputMap<A, I>>,
+    mut action_state: ResMut<ActionState<A>>,
+) where
+    A: Ord + Hash + Clone + Send + Sync + 'static,
+    I: ActionInput,
+{
+    let Some(input) = input else {
+        return;
+    };
+
+    // Not every backend runs Bevy's time plugin, fall back to the wall clock.
+    let now = Instant::now();
+    let delta = match time {
+        Some(time) => time.delta(),
+        None => action_state
+            .last_update
+            .map_or(Duration::ZERO, |last_update| now - last_update),
+    };
+    action_state.last_update = Some(now);
+
+    action_state.update(&input_map, &input, delta);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashSet;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+    enum Action {
+        Up,
+        Run,
+        Fire,
+    }
+
+    #[derive(Default, Resource)]
+    struct TestInput {
+        keys: HashSet<KeyCode>,
+        mouse_buttons: HashSet<MouseButton>,
+        modifiers: Modifiers,
+    }
+
+    impl ActionInput for TestInput {
+        type Key = KeyCode;
+        type MouseButton = MouseButton;
+        type KeyNames = SerdeNames<KeyCode>;
+        type MouseButtonNames = SerdeNames<MouseButton>;
+
+        fn key_modifiers(key: KeyCode) -> Modifiers {
+            ConsoleInput::key_modifiers(key)
+        }
+
+        fn key_down(&self, key: KeyCode) -> bool {
+            self.keys.contains(&key)
+        }
+
+        fn mouse_button_down(&self, mouse_button: MouseButton) -> bool {
+            self.mouse_buttons.contains(&mouse_button)
+        }
+
+        fn modifiers(&self) -> Modifiers {
+            self.modifiers
+        }
+    }
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    fn input_map() -> InputMap<Action, TestInput> {
+        InputMap::default()
+            .with_binding(
+                Action::Up,
+                Binding::key(KeyCode::Up).with_repeat(Repeat {
+                    delay: Duration::from_millis(250),
+                    interval: Duration::from_millis(100),
+                }),
+            )
+            .with_binding(
+                Action::Run,
+                Binding::key(KeyCode::Up).with_modifiers(Modifiers::SHIFT),
+            )
+            .with_binding(
+                Action::Fire,
+                Binding::chord([
+                    Trigger::Key(KeyCode::Space),
+                    Trigger::Mouse(MouseButton::Left),
+                ]),
+            )
+    }
+
+    #[test]
+    fn press_hold_and_release() {
+        let input_map = input_map();
+        let mut state = ActionState::default();
+        let mut input = TestInput::default();
+
+        state.update(&input_map, &input, FRAME);
+        assert!(state.released(&Action::Up));
+        assert!(!state.just_released(&Action::Up));
+
+        input.keys.insert(KeyCode::Up);
+        state.update(&input_map, &input, FRAME);
+        assert!(state.pressed(&Action::Up));
+        assert!(state.just_pressed(&Action::Up));
+        assert_eq!(state.held_for(&Action::Up), Duration::ZERO);
+
+        state.update(&input_map, &input, FRAME);
+        assert!(state.pressed(&Action::Up));
+        assert!(!state.just_pressed(&Action::Up));
+        assert_eq!(state.held_for(&Action::Up), FRAME);
+
+        input.keys.clear();
+        state.update(&input_map, &input, FRAME);
+        assert!(state.released(&Action::Up));
+        assert!(state.just_released(&Action::Up));
+        assert_eq!(state.held_for(&Action::Up), Duration::ZERO);
+    }
+
+    #[test]
+    fn repeats_while_held() {
+        let input_map = input_map();
+        let mut state = ActionState::default();
+        let input = TestInput {
+            keys: [KeyCode::Up].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let just_pressed: Vec<_> = (0..7)
+            .map(|_| {
+                state.update(&input_map, &input, FRAME);
+                state.just_pressed(&Action::Up)
+            })
+            .collect();
+        // Pressed, then repeated after 250ms, every 100ms.
+        assert_eq!(just_pressed, [true, false, false, true, true, true, true]);
+    }
+
+    #[test]
+    fn modifiers_must_match_exactly() {
+        let input_map = input_map();
+        let mut state = ActionState::default();
+        let mut input = TestInput {
+            keys: [KeyCode::Up].into_iter().collect(),
+            ..Default::default()
+        };
+
+        state.update(&input_map, &input, FRAME);
+        assert!(state.pressed(&Action::Up));
+        assert!(state.released(&Action::Run));
+
+        input.modifiers = Modifiers::SHIFT;
+        state.update(&input_map, &input, FRAME);
+        assert!(state.just_released(&Action::Up));
+        assert!(state.just_pressed(&Action::Run));
+
+        input.modifiers = Modifiers {
+            shift: true,
+            ctrl: true,
+            alt: false,
+        };
+        state.update(&input_map, &input, FRAME);
+        assert!(state.released(&Action::Up));
+        assert!(state.just_released(&Action::Run));
+    }
+
+    #[test]
+    fn modifier_keys_as_triggers() {
+        let input_map = InputMap::<Action, TestInput>::default()
+            .with_binding(Action::Run, Binding::key(KeyCode::LShift))
+            .with_binding(
+                Action::Fire,
+                Binding::chord([Trigger::Key(KeyCode::LControl), Trigger::Key(KeyCode::S)]),
+            );
+        let mut state = ActionState::default();
+        let mut input = TestInput {
+            keys: [KeyCode::LShift].into_iter().collect(),
+            modifiers: Modifiers::SHIFT,
+            ..Default::default()
+        };
+
+        state.update(&input_map, &input, FRAME);
+        assert!(state.just_pressed(&Action::Run));
+        assert!(state.released(&Action::Fire));
+
+        input.keys = [KeyCode::LControl, KeyCode::S].into_iter().collect();
+        input.modifiers = Modifiers::CTRL;
+        state.update(&input_map, &input, FRAME);
+        assert!(state.just_released(&Action::Run));
+        assert!(state.just_pressed(&Action::Fire));
+
+        // Shift is an extra modifier for the chord, and ctrl for the shift binding
+        input.keys.insert(KeyCode::LShift);
+        input.modifiers = Modifiers {
+            shift: true,
+            ctrl: true,
+            alt: false,
+        };
+        state.update(&input_map, &input, FRAME);
+        assert!(state.released(&Action::Run));
+        assert!(state.just_released(&Action::Fire));
+    }
+
+    #[test]
+    fn chords_need_every_trigger() {
+        let input_map = input_map();
+        let mut state = ActionState::default();
+        let mut input = TestInput {
+            keys: [KeyCode::Space].into_iter().collect(),
+            ..Default::default()
+        };
+
+        state.update(&input_map, &input, FRAME);
+        assert!(state.released(&Action::Fire));
+
+        input.mouse_buttons.insert(MouseButton::Left);
+        state.update(&input_map, &input, FRAME);
+        assert!(state.just_pressed(&Action::Fire));
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let input_map = input_map();
+
+        let ron = input_map.to_ron_string().unwrap();
+        let parsed = InputMap::<Action, TestInput>::from_ron_str(&ron).unwrap();
+
+        assert_eq!(parsed.iter().count(), 3);
+        for (action, bindings) in input_map.iter() {
+            assert_eq!(parsed.bindings(action), bindings);
+        }
+        assert_eq!(parsed.to_ron_string().unwrap(), ron);
+    }
+
+    #[test]
+    fn ron_defaults() {
+        let parsed = InputMap::<Action, TestInput>::from_ron_str(
+            "(bindings: { Up: [(triggers: [Key(\"Up\")])] })",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.bindings(&Action::Up), [Binding::key(KeyCode::Up)]);
+        assert!(parsed.bindings(&Action::Run).is_empty());
+        assert!(matches!(
+            InputMap::<Action, TestInput>::from_ron_str("(bindings: { Jump: [] })"),
+            Err(InputMapError::Parse(_))
+        ));
+        assert!(matches!(
+            InputMap::<Action, TestInput>::from_ron_str(
+                "(bindings: { Up: [(triggers: [Key(\"Hyper\")])] })"
+            ),
+            Err(InputMapError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+//! In-memory console.
+
+use crate::{ConsoleApi, Rgba};
+
+/// A single cell of a [`CellBuffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    /// The glyph drawn in the cell.
+    pub glyph: char,
+    /// The foreground color of the cell.
+    pub fore: Rgba,
+    /// The background color of the cell.
+    pub back: Rgba,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            glyph: ' ',
+            fore: (255, 255, 255, 255),
+            back: (0, 0, 0, 255),
+        }
+    }
+}
+
+/// A plain grid of [`Cell`]s, drawn on through the [`ConsoleApi`] trait.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellBuffer {
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+}
+
+impl CellBuffer {
+    /// Create a buffer filled with default cells.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); (width * height) as usize],
+        }
+    }
+
+    /// Resize the buffer, discarding its content.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        *self = Self::new(width, height);
+    }
+
+    /// Returns the cell at the given position, if it is inside the buffer.
+    pub fn cell(&self, x: i32, y: i32) -> Option<&Cell> {
+        self.index(x, y).map(|index| &self.cells[index])
+    }
+
+    /// Returns all the cells, row by row.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Returns the glyphs of a row as a string, if the row is inside the buffer.
+    pub fn row_text(&self, y: i32) -> Option<String> {
+        (y >= 0 && (y as u32) < self.height).then(|| {
+            let start = (y as u32 * self.width) as usize;
+            self.cells[start..start + self.width as usize]
+                .iter()
+                .map(|cell| cell.glyph)
+                .collect()
+        })
+    }
+
+    /// Wraps a console so that everything drawn on it is also recorded in this buffer.
+    ///
+    /// This makes write only consoles readable, for example to take a
+    /// [`ConsoleSnapshot`](crate::ConsoleSnapshot) of them. The buffer is resized to the
+    /// console size if needed.
+    pub fn mirror<'a, C: ConsoleApi + ?Sized>(
+        &'a mut self,
+        console: &'a mut C,
+    ) -> MirroredConsole<'a, C> {
+        let (width, height) = console.size();
+        if (width, height) != (self.width, self.height) {
+            self.resize(width, height);
+        }
+
+        MirroredConsole {
+            buffer: self,
+            console,
+        }
+    }
+
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        self.contains(x, y)
+            .then(|| (x as u32 + y as u32 * self.width) as usize)
+    }
+}
+
+impl ConsoleApi for CellBuffer {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        let Some(index) = self.index(x, y) else {
+            return;
+        };
+
+        let cell = &mut self.cells[index];
+        if let Some(glyph) = glyph {
+            cell.glyph = glyph;
+        }
+        if let Some(fore) = fore {
+            cell.fore = fore;
+        }
+        if let Some(back) = back {
+            cell.back = back;
+        }
+    }
+
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char> {
+        self.cell(x, y).map(|cell| cell.glyph)
+    }
+
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.cell(x, y).map(|cell| cell.fore)
+    }
+
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.cell(x, y).map(|cell| cell.back)
+    }
+}
+
+/// A console whose writes are recorded in a [`CellBuffer`]. See [`CellBuffer::mirror`].
+pub struct MirroredConsole<'a, C: ConsoleApi + ?Sized> {
+    buffer: &'a mut CellBuffer,
+    console: &'a mut C,
+}
+
+impl<'a, C: ConsoleApi + ?Sized> ConsoleApi for MirroredConsole<'a, C> {
+    fn size(&self) -> (u32, u32) {
+        self.console.size()
+    }
+
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        self.buffer.put_cell(x, y, glyph, fore, back);
+        self.console.put_cell(x, y, glyph, fore, back);
+    }
+
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char> {
+        self.buffer.get_glyph(x, y)
+    }
+
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.buffer.get_fore(x, y)
+    }
+
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba> {
+        self.buffer.get_back(x, y)
+    }
+
+    fn clear(&mut self, fore: Option<Rgba>, back: Option<Rgba>, glyph: Option<char>) {
+        self.buffer.clear(fore, back, glyph);
+        self.console.clear(fore, back, glyph);
+    }
+}
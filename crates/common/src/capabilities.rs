@@ -0,0 +1,41 @@
+//! What each backend is able to do.
+
+use bevy::prelude::Resource;
+
+/// The features supported by the running [`Backend`](crate::Backend), inserted as a resource
+/// when the backend is built.
+///
+/// Check it at runtime to fall back gracefully, for instance to draw a map with full cells
+/// when subcell rendering is not available.
+///
+/// Every backend lists all of them, there is no default.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    /// The name of the backend, the name it is registered under in the `brltk` backend
+    /// registry.
+    pub name: &'static str,
+    /// Images can be blitted at twice the console resolution, using half and quarter cell
+    /// glyphs.
+    pub subcell: bool,
+    /// Colors are blended with what is below them according to their alpha channel.
+    pub alpha_blending: bool,
+    /// Colors are drawn as given, rather than mapped to a fixed palette.
+    pub true_color: bool,
+    /// Colors can be referred to by name, through a registered palette.
+    pub named_colors: bool,
+    /// Several consoles can be drawn on top of each other.
+    pub layers: bool,
+    /// Layers can leave cells transparent, showing the layers below them.
+    pub sparse_layers: bool,
+    /// Several fonts can be used at the same time, one per console.
+    pub multiple_fonts: bool,
+    /// The font can be changed while the game is running.
+    pub runtime_font_change: bool,
+    /// The number of cells of the consoles changes when the window is resized, see
+    /// [`ResizePolicy`](crate::ResizePolicy).
+    pub runtime_resize: bool,
+    /// The backend draws borders around its consoles itself.
+    pub borders: bool,
+    /// The console is displayed in a window.
+    pub windowed: bool,
+}
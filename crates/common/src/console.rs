@@ -0,0 +1,159 @@
+//! Backend agnostic drawing interface.
+
+/// An RGBA color, using the same layout as `doryen-rs` colors.
+pub type Rgba = (u8, u8, u8, u8);
+
+/// Horizontal alignment used when printing text on a console.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    /// The text starts at the given position.
+    #[default]
+    Left,
+    /// The text ends at the given position.
+    Right,
+    /// The text is centered on the given position.
+    Center,
+}
+
+/// A drawing surface made of glyph cells.
+///
+/// Every backend implements this trait for its console type so that drawing code can be
+/// written once and reused whichever backend is enabled. Coordinates always start at the
+/// top left corner of the console, with `y` growing downwards. Writing outside of the
+/// console is silently ignored.
+pub trait ConsoleApi {
+    /// Returns the size of the console in cells.
+    fn size(&self) -> (u32, u32);
+
+    /// Sets the glyph, foreground and background of a cell. `None` values leave the
+    /// corresponding part of the cell unchanged.
+    fn put_cell(
+        &mut self,
+        x: i32,
+        y: i32,
+        glyph: Option<char>,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    );
+
+    /// Returns the glyph of a cell, or `None` if the position is outside of the console or
+    /// the backend cannot read its cells back.
+    fn get_glyph(&self, x: i32, y: i32) -> Option<char>;
+
+    /// Returns the foreground color of a cell, or `None` if the position is outside of the
+    /// console or the backend cannot read its cells back.
+    fn get_fore(&self, x: i32, y: i32) -> Option<Rgba>;
+
+    /// Returns the background color of a cell, or `None` if the position is outside of the
+    /// console or the backend cannot read its cells back.
+    fn get_back(&self, x: i32, y: i32) -> Option<Rgba>;
+
+    /// Returns the width of the console in cells.
+    fn width(&self) -> u32 {
+        self.size().0
+    }
+
+    /// Returns the height of the console in cells.
+    fn height(&self) -> u32 {
+        self.size().1
+    }
+
+    /// Returns true if the position is inside the console.
+    fn contains(&self, x: i32, y: i32) -> bool {
+        let (width, height) = self.size();
+        x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height
+    }
+
+    /// Sets the glyph of a cell.
+    fn put_glyph(&mut self, x: i32, y: i32, glyph: char) {
+        self.put_cell(x, y, Some(glyph), None, None);
+    }
+
+    /// Sets the foreground color of a cell.
+    fn set_fore(&mut self, x: i32, y: i32, fore: Rgba) {
+        self.put_cell(x, y, None, Some(fore), None);
+    }
+
+    /// Sets the background color of a cell.
+    fn set_back(&mut self, x: i32, y: i32, back: Rgba) {
+        self.put_cell(x, y, None, None, Some(back));
+    }
+
+    /// Fills the whole console. `None` values leave the corresponding part of the cells
+    /// unchanged.
+    fn clear(&mut self, fore: Option<Rgba>, back: Option<Rgba>, glyph: Option<char>) {
+        let (width, height) = self.size();
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                self.put_cell(x, y, glyph, fore, back);
+            }
+        }
+    }
+
+    /// Prints a single line of text, aligned relatively to the given position.
+    fn print(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        align: TextAlign,
+        fore: Option<Rgba>,
+        back: Option<Rgba>,
+    ) {
+        let len = text.chars().count() as i32;
+        let start = match align {
+            TextAlign::Left => x,
+            TextAlign::Right => x - len + 1,
+            TextAlign::Center => x - len / 2,
+        };
+        for (i, c) in text.chars().enumerate() {
+            self.put_cell(start + i as i32, y, Some(c), fore, back);
+        }
+    }
+
+    /// Copies this console onto `destination`, with its top left corner at the given
+    /// position.
+    ///
+    /// The alpha values blend the foreground and background colors with the destination,
+    /// and cells whose background matches `key_color` are skipped. Cells that cannot be
+    /// read back from this console are skipped as well.
+    fn blit(
+        &self,
+        x: i32,
+        y: i32,
+        destination: &mut dyn ConsoleApi,
+        fore_alpha: f32,
+        back_alpha: f32,
+        key_color: Option<Rgba>,
+    ) {
+        let (width, height) = self.size();
+        for sy in 0..height as i32 {
+            for sx in 0..width as i32 {
+                let (dx, dy) = (x + sx, y + sy);
+                if !destination.contains(dx, dy) {
+                    continue;
+                }
+
+                let back = self.get_back(sx, sy);
+                if key_color.is_some() && back == key_color {
+                    continue;
+                }
+
+                let fore = self
+                    .get_fore(sx, sy)
+                    .map(|fore| blend(destination.get_fore(dx, dy), fore, fore_alpha));
+                let back = back.map(|back| blend(destination.get_back(dx, dy), back, back_alpha));
+                destination.put_cell(dx, dy, self.get_glyph(sx, sy), fore, back);
+            }
+        }
+    }
+}
+
+/// Blends `color` over `base` with the given alpha, also taking the alpha channel of `color`
+/// into account. A missing `base` is treated as opaque black.
+pub fn blend(base: Option<Rgba>, color: Rgba, alpha: f32) -> Rgba {
+    let (br, bg, bb, ba) = base.unwrap_or((0, 0, 0, 255));
+    let alpha = (alpha * color.3 as f32 / 255.0).clamp(0.0, 1.0);
+    let mix = |b: u8, c: u8| (b as f32 + (c as f32 - b as f32) * alpha).round() as u8;
+    (mix(br, color.0), mix(bg, color.1), mix(bb, color.2), ba)
+}
@@ -0,0 +1,273 @@
+//! Description of the font images shared by the backends.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+/// How the glyphs of a font are ordered in its image.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Charset {
+    /// The 256 glyphs of the IBM code page 437, row by row.
+    #[default]
+    Cp437,
+    /// The glyph of every Unicode code point, from `U+0000`, row by row.
+    Unicode,
+}
+
+/// The description of a font image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontInfo {
+    /// The name of the font in the [`FontRegistry`].
+    pub name: String,
+    /// The path of the image, relative to the asset folder.
+    pub path: String,
+    /// The size of a glyph in pixels.
+    pub cell_size: (u32, u32),
+    /// The number of glyph columns and rows in the image.
+    pub grid: (u32, u32),
+    /// How the glyphs are ordered in the image.
+    #[serde(default)]
+    pub charset: Charset,
+    /// The image has an alpha channel for the transparent pixels.
+    #[serde(default)]
+    pub has_alpha: bool,
+}
+
+impl FontInfo {
+    /// Returns the size of the image in pixels.
+    pub fn image_size(&self) -> (u32, u32) {
+        (
+            self.cell_size.0 * self.grid.0,
+            self.cell_size.1 * self.grid.1,
+        )
+    }
+
+    /// Returns the number of glyphs in the image.
+    pub fn glyph_count(&self) -> u32 {
+        self.grid.0 * self.grid.1
+    }
+}
+
+/// The fonts known to the game, looked up by name by the backend builders.
+///
+/// The registry is usually read from a RON manifest listing the fonts, like the
+/// `assets/fonts.ron` file describing the fonts bundled with BRLTK:
+///
+/// ```ron
+/// (
+///     fonts: [
+///         (
+///             name: "terminal_8x8",
+///             path: "terminal_8x8.png",
+///             cell_size: (8, 8),
+///             grid: (16, 16),
+///             charset: Cp437,
+///             has_alpha: true,
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FontRegistry {
+    fonts: Vec<FontInfo>,
+}
+
+impl FontRegistry {
+    /// Create a registry without any font.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a registry from the content of a RON manifest.
+    pub fn from_ron(manifest: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(manifest)
+    }
+
+    /// Read a registry from a RON manifest file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FontManifestError> {
+        let path = path.as_ref();
+        let manifest = std::fs::read_to_string(path)
+            .map_err(|e| FontManifestError::Io(path.to_path_buf(), e))?;
+        Self::from_ron(&manifest).map_err(|e| FontManifestError::Manifest(path.to_path_buf(), e))
+    }
+
+    /// Register a font, replacing the font with the same name.
+    pub fn register(mut self, font: FontInfo) -> Self {
+        match self.fonts.iter_mut().find(|f| f.name == font.name) {
+            Some(f) => *f = font,
+            None => self.fonts.push(font),
+        }
+        self
+    }
+
+    /// Returns the font with the given name.
+    pub fn get(&self, name: &str) -> Option<&FontInfo> {
+        self.fonts.iter().find(|font| font.name == name)
+    }
+
+    /// Returns the font with the given name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no font is registered under this name.
+    pub fn expect(&self, name: &str) -> &FontInfo {
+        self.get(name).unwrap_or_else(|| {
+            panic!(
+                "unknown font `{name}`, expected one of: {}",
+                self.names().collect::<Vec<_>>().join(", ")
+            )
+        })
+    }
+
+    /// Returns an iterator over the fonts, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &FontInfo> + '_ {
+        self.fonts.iter()
+    }
+
+    /// Returns an iterator over the names of the fonts, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.fonts.iter().map(|font| font.name.as_str())
+    }
+
+    /// Returns the number of registered fonts.
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Returns true if no font is registered.
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+}
+
+/// Send this event to draw every console with another font, for instance from an options
+/// menu. The backends switching fonts at runtime report
+/// [`runtime_font_change`](crate::BackendCapabilities::runtime_font_change).
+///
+/// The cells keep their count: the window or the terminal layout is recomputed for the
+/// cell size of the new font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeFont(pub FontInfo);
+
+impl ChangeFont {
+    /// Returns the event switching to the font with the given name, if it is registered.
+    pub fn named(registry: &FontRegistry, name: &str) -> Option<Self> {
+        registry.get(name).cloned().map(Self)
+    }
+}
+
+/// An error raised while reading a [`FontRegistry`] manifest.
+#[derive(Debug)]
+pub enum FontManifestError {
+    /// The manifest file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The manifest file is not valid.
+    Manifest(PathBuf, ron::error::SpannedError),
+}
+
+impl fmt::Display for FontManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "cannot read {}: {e}", path.display()),
+            Self::Manifest(path, e) => write!(f, "invalid font manifest {}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for FontManifestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_ron() {
+        let registry = FontRegistry::from_ron(
+            r#"(
+                fonts: [
+                    (
+                        name: "terminal_8x8",
+                        path: "terminal_8x8.png",
+                        cell_size: (8, 8),
+                        grid: (16, 16),
+                        charset: Cp437,
+                        has_alpha: true,
+                    ),
+                    (
+                        name: "unicode",
+                        path: "unicode_16x16.png",
+                        cell_size: (16, 16),
+                        grid: (256, 256),
+                        charset: Unicode,
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            ["terminal_8x8", "unicode"]
+        );
+        let terminal = registry.expect("terminal_8x8");
+        assert_eq!(terminal.image_size(), (128, 128));
+        assert_eq!(terminal.glyph_count(), 256);
+        assert!(terminal.has_alpha);
+        let unicode = registry.expect("unicode");
+        assert_eq!(unicode.charset, Charset::Unicode);
+        assert!(!unicode.has_alpha);
+    }
+
+    #[test]
+    fn charset_defaults_to_cp437() {
+        let registry = FontRegistry::from_ron(
+            r#"(fonts: [(name: "a", path: "a.png", cell_size: (8, 8), grid: (16, 16))])"#,
+        )
+        .unwrap();
+        assert_eq!(registry.expect("a").charset, Charset::Cp437);
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        assert!(FontRegistry::from_ron("(fonts: [(name: \"a\")])").is_err());
+        assert!(FontRegistry::from_ron("(fonts: [(name: \"a\", path: 1)])").is_err());
+        assert!(FontRegistry::from_ron("").is_err());
+    }
+
+    #[test]
+    fn register_replaces_by_name() {
+        let font = |path: &str| FontInfo {
+            name: String::from("font"),
+            path: String::from(path),
+            cell_size: (8, 8),
+            grid: (16, 16),
+            charset: Charset::Cp437,
+            has_alpha: false,
+        };
+        let registry = FontRegistry::new()
+            .register(font("a.png"))
+            .register(font("b.png"));
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.expect("font").path, "b.png");
+        assert!(registry.get("other").is_none());
+    }
+
+    #[test]
+    fn bundled_manifest() {
+        let registry = FontRegistry::from_ron(include_str!("../../../assets/fonts.ron")).unwrap();
+        let mut names: Vec<_> = registry.names().collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), registry.len());
+        for font in registry
+            .iter()
+            .filter(|font| font.charset == Charset::Cp437)
+        {
+            assert_eq!(font.glyph_count(), 256, "{}", font.name);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Backend agnostic input.
+
+use bevy::{
+    prelude::{Input, KeyCode, MouseButton, Resource, SystemLabel},
+    utils::HashSet,
+};
+
+/// The label of the backend systems filling the [`ConsoleInput`] resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub struct ConsoleInputSystem;
+
+/// Provides access to the input of the current frame, whichever backend is enabled.
+///
+/// Every backend inserts this resource and fills it before the
+/// [`CoreStage::Update`](bevy::prelude::CoreStage::Update) systems run, so input systems
+/// written against it are portable between backends. Keys reported by a backend without a
+/// matching [`KeyCode`] are left out.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct ConsoleInput {
+    text: String,
+    close_requested: bool,
+
+    keys_down: HashSet<KeyCode>,
+    keys_pressed: HashSet<KeyCode>,
+    keys_released: HashSet<KeyCode>,
+
+    mouse_position: (f32, f32),
+    mouse_buttons_down: HashSet<MouseButton>,
+    mouse_buttons_pressed: HashSet<MouseButton>,
+    mouse_buttons_released: HashSet<MouseButton>,
+}
+
+impl ConsoleInput {
+    /// Returns the current status of the given key (true if currently pressed).
+    pub fn key(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// Returns true if the given key was pressed since the last update.
+    pub fn key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Returns an iterator over all the keys that were pressed since the last update in no
+    /// particular order.
+    pub fn keys_pressed(&self) -> impl Iterator<Item = &KeyCode> + '_ {
+        self.keys_pressed.iter()
+    }
+
+    /// Returns true if the given key was released since the last update.
+    pub fn key_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// Returns an iterator over all the keys that were released since the last update in no
+    /// particular order.
+    pub fn keys_released(&self) -> impl Iterator<Item = &KeyCode> + '_ {
+        self.keys_released.iter()
+    }
+
+    /// Returns an iterator over all the keys that are currently pressed in no particular
+    /// order.
+    pub fn keys_down(&self) -> impl Iterator<Item = &KeyCode> + '_ {
+        self.keys_down.iter()
+    }
+
+    /// Characters typed since last update.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the current status of the given mouse button (true if currently pressed).
+    pub fn mouse_button(&self, mouse_button: MouseButton) -> bool {
+        self.mouse_buttons_down.contains(&mouse_button)
+    }
+
+    /// Returns true if the given mouse button was pressed since the last update.
+    pub fn mouse_pressed(&self, mouse_button: MouseButton) -> bool {
+        self.mouse_buttons_pressed.contains(&mouse_button)
+    }
+
+    /// Returns true if the given mouse button was released since the last update.
+    pub fn mouse_released(&self, mouse_button: MouseButton) -> bool {
+        self.mouse_buttons_released.contains(&mouse_button)
+    }
+
+    /// Returns the current mouse position in console cells coordinates.
+    /// The decimal part of the value indicates sub-cell location, for the backends reporting
+    /// it.
+    pub fn mouse_position(&self) -> (f32, f32) {
+        self.mouse_position
+    }
+
+    /// Returns the console cell under the mouse.
+    pub fn mouse_cell(&self) -> (i32, i32) {
+        (
+            self.mouse_position.0.floor() as i32,
+            self.mouse_position.1.floor() as i32,
+        )
+    }
+
+    /// Whether the window close button has been activated.
+    pub fn close_requested(&self) -> bool {
+        self.close_requested
+    }
+}
+
+/// Methods used by the backends to fill the resource.
+impl ConsoleInput {
+    /// Clears the per-frame state. Called by the backends before reporting the input of a
+    /// new frame.
+    pub fn on_frame(&mut self) {
+        self.text.clear();
+        self.close_requested = false;
+
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+
+        self.mouse_buttons_pressed.clear();
+        self.mouse_buttons_released.clear();
+    }
+
+    /// Reports a key press.
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.keys_pressed.insert(key);
+        self.keys_down.insert(key);
+    }
+
+    /// Reports a key release.
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.keys_released.insert(key);
+        self.keys_down.remove(&key);
+    }
+
+    /// Reports typed text.
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Reports the mouse position in console cells coordinates.
+    pub fn set_mouse_position(&mut self, mouse_position: (f32, f32)) {
+        self.mouse_position = mouse_position;
+    }
+
+    /// Reports a mouse button press.
+    pub fn press_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.mouse_buttons_pressed.insert(mouse_button);
+        self.mouse_buttons_down.insert(mouse_button);
+    }
+
+    /// Reports a mouse button release.
+    pub fn release_mouse_button(&mut self, mouse_button: MouseButton) {
+        self.mouse_buttons_released.insert(mouse_button);
+        self.mouse_buttons_down.remove(&mouse_button);
+    }
+
+    /// Reports a click on the window close button.
+    pub fn request_close(&mut self) {
+        self.close_requested = true;
+    }
+
+    /// Reports the state of Bevy's own input resources, for backends running on top of a
+    /// Bevy window.
+    pub fn update_from_bevy(
+        &mut self,
+        keys: &Input<KeyCode>,
+        mouse_buttons: &Input<MouseButton>,
+        text: impl IntoIterator<Item = char>,
+        mouse_position: Option<(f32, f32)>,
+        close_requested: bool,
+    ) {
+        self.on_frame();
+
+        keys.get_just_pressed().for_each(|&key| self.press_key(key));
+        keys.get_just_released()
+            .for_each(|&key| self.release_key(key));
+        mouse_buttons
+            .get_just_pressed()
+            .for_each(|&button| self.press_mouse_button(button));
+        mouse_buttons
+            .get_just_released()
+            .for_each(|&button| self.release_mouse_button(button));
+
+        self.text
+            .extend(text.into_iter().filter(|c| !c.is_control()));
+        if let Some(mouse_position) = mouse_position {
+            self.mouse_position = mouse_position;
+        }
+        self.close_requested = close_requested;
+    }
+}
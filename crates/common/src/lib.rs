@@ -1,3 +1,38 @@
+//! `brltk_common` contains the types shared between all the BRLTK backends.
+
+mod action;
+pub use action::*;
+mod buffer;
+pub use buffer::*;
+mod capabilities;
+pub use capabilities::*;
+mod console;
+pub use console::*;
+mod font;
+pub use font::*;
+mod input;
+pub use input::*;
+mod lifecycle;
+pub use lifecycle::*;
+mod naming;
+pub use naming::*;
+mod resize;
+pub use resize::*;
+mod snapshot;
+pub use snapshot::*;
+
+/// A roguelike library backend, responsible for setting up the Bevy [`App`](bevy::app::App).
 pub trait Backend: Send + Sync {
+    /// Adds the backend plugins, resources and runner to the app.
     fn build(&self, app: &mut bevy::app::App);
+
+    /// Describes the features supported by the backend, inserted as the
+    /// [`BackendCapabilities`] resource when the backend is built.
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// Called at the end of the frame for every [`AppLifecycle`] event sent during it.
+    fn on_lifecycle(&self, _world: &mut bevy::prelude::World, _event: AppLifecycle) {}
+
+    /// Called at the end of the last frame, right before the app exits.
+    fn cleanup(&self, _world: &mut bevy::prelude::World) {}
 }
@@ -0,0 +1,122 @@
+//! Backend agnostic lifecycle events.
+
+use bevy::{
+    app::AppExit,
+    prelude::{
+        App, CoreStage, EventReader, EventWriter, Events, IntoSystemDescriptor, Local, Plugin,
+        Resource, SystemLabel, World,
+    },
+    window::{WindowFocused, WindowId, WindowResized},
+};
+
+/// A change in the lifecycle of the app. Every backend sends the events it is able to
+/// detect, see each variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppLifecycle {
+    /// The app runs its first frame. Sent by every backend.
+    Started,
+    /// The window lost the focus. Sent by the windowed backends running on `winit`, and by
+    /// the crossterm backend when the terminal reports focus changes.
+    FocusLost,
+    /// The window gained the focus. Sent by the windowed backends running on `winit`, and by
+    /// the crossterm backend when the terminal reports focus changes.
+    FocusGained,
+    /// The window was minimized. Sent by the windowed backends running on `winit`, on the
+    /// platforms reporting a zero size for minimized windows.
+    Suspended,
+    /// The window was restored after being minimized.
+    Resumed,
+    /// The app is exiting: this is the last frame, save the game now. Sent by every backend
+    /// after an [`AppExit`] event or, for the windowed backends, after the window was closed.
+    Exiting,
+}
+
+/// The label of the system delaying the [`AppExit`] events by one frame to send
+/// [`AppLifecycle::Exiting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+pub struct LifecycleSystem;
+
+/// Whether the app is exiting.
+#[derive(Default, Debug, Clone, Copy, Resource)]
+pub struct LifecycleState {
+    exiting: bool,
+}
+
+impl LifecycleState {
+    /// Returns true during the last frame, once [`AppLifecycle::Exiting`] was sent.
+    pub fn is_exiting(&self) -> bool {
+        self.exiting
+    }
+}
+
+/// Sends the [`AppLifecycle`] events common to every backend.
+///
+/// The first [`AppExit`] event is held back for one frame: [`AppLifecycle::Exiting`] is
+/// sent instead, and the app exits at the end of the next frame, once the systems had a
+/// chance to react to it.
+pub struct LifecyclePlugin;
+
+impl Plugin for LifecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AppLifecycle>()
+            .init_resource::<LifecycleState>()
+            .add_startup_system(send_started)
+            .add_system_to_stage(CoreStage::Last, delay_app_exit.label(LifecycleSystem));
+    }
+}
+
+fn send_started(mut lifecycle: EventWriter<AppLifecycle>) {
+    lifecycle.send(AppLifecycle::Started);
+}
+
+fn delay_app_exit(world: &mut World) {
+    if world.resource::<LifecycleState>().exiting {
+        world.resource_mut::<Events<AppExit>>().send(AppExit);
+        return;
+    }
+
+    let mut app_exit_events = world.resource_mut::<Events<AppExit>>();
+    if app_exit_events.is_empty() {
+        return;
+    }
+    app_exit_events.clear();
+    world.resource_mut::<LifecycleState>().exiting = true;
+    world
+        .resource_mut::<Events<AppLifecycle>>()
+        .send(AppLifecycle::Exiting);
+}
+
+/// Sends the focus and minimization [`AppLifecycle`] events of the primary window. Added by
+/// the backends running on `winit`.
+pub fn track_window_lifecycle(
+    mut focused_events: EventReader<WindowFocused>,
+    mut resized_events: EventReader<WindowResized>,
+    mut lifecycle: EventWriter<AppLifecycle>,
+    mut suspended: Local<bool>,
+) {
+    for focused in focused_events
+        .iter()
+        .filter(|focused| focused.id == WindowId::primary())
+    {
+        lifecycle.send(if focused.focused {
+            AppLifecycle::FocusGained
+        } else {
+            AppLifecycle::FocusLost
+        });
+    }
+
+    for resized in resized_events
+        .iter()
+        .filter(|resized| resized.id == WindowId::primary())
+    {
+        let minimized = resized.width == 0.0 || resized.height == 0.0;
+        if minimized != *suspended {
+            *suspended = minimized;
+            lifecycle.send(if minimized {
+                AppLifecycle::Suspended
+            } else {
+                AppLifecycle::Resumed
+            });
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Saving input types by name, for the types without serde support.
+
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Names the values of an input type, to save them in files.
+///
+/// The trait is implemented by a marker type rather than by the named type, so the backends
+/// can name the types of their library.
+pub trait Names: 'static {
+    /// The named type.
+    type Value: Copy;
+
+    /// Returns the name of the value, or `None` if it cannot be saved.
+    fn name(value: Self::Value) -> Option<Cow<'static, str>>;
+
+    /// Returns the value with the given name.
+    fn from_name(name: &str) -> Option<Self::Value>;
+}
+
+/// Names the values of a serde type with their RON representation, like `Up` or `Other(4)`.
+pub struct SerdeNames<T>(PhantomData<fn() -> T>);
+
+impl<T: Copy + Serialize + DeserializeOwned + 'static> Names for SerdeNames<T> {
+    type Value = T;
+
+    fn name(value: T) -> Option<Cow<'static, str>> {
+        ron::to_string(&value).ok().map(Cow::Owned)
+    }
+
+    fn from_name(name: &str) -> Option<T> {
+        ron::from_str(name).ok()
+    }
+}
+
+/// Serde adapter saving a list of values by name with the [`Names`] `N`, through
+/// `serialize_with = "names::serialize::<N, _>"` and
+/// `deserialize_with = "names::deserialize::<N, _>"`. A value without a name and an unknown
+/// name are errors.
+pub mod names {
+    use super::*;
+    use serde::{
+        de::{SeqAccess, Visitor},
+        ser::SerializeSeq,
+        Deserializer, Serializer,
+    };
+
+    /// Saves the values by name.
+    pub fn serialize<N: Names, S: Serializer>(
+        values: &[N::Value],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for &value in values {
+            seq.serialize_element(&name::<N, S::Error>(value)?)?;
+        }
+        seq.end()
+    }
+
+    /// Reads the values from their names.
+    pub fn deserialize<'de, N: Names, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<N::Value>, D::Error> {
+        deserializer.deserialize_seq(NamesVisitor::<N>(PhantomData))
+    }
+
+    /// Returns the name of the value, or an error if it has none.
+    pub fn name<N: Names, E: serde::ser::Error>(value: N::Value) -> Result<Cow<'static, str>, E> {
+        N::name(value).ok_or_else(|| E::custom("the input cannot be saved by name"))
+    }
+
+    /// Returns the value with the given name, or an error if the name is unknown.
+    pub fn from_name<N: Names, E: serde::de::Error>(name: &str) -> Result<N::Value, E> {
+        N::from_name(name).ok_or_else(|| E::custom(format!("unknown input name {name}")))
+    }
+
+    struct NamesVisitor<N>(PhantomData<N>);
+
+    impl<'de, N: Names> Visitor<'de> for NamesVisitor<N> {
+        type Value = Vec<N::Value>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of names")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut values = Vec::new();
+            while let Some(name) = seq.next_element::<Cow<'de, str>>()? {
+                values.push(from_name::<N, A::Error>(&name)?);
+            }
+            Ok(values)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{KeyCode, MouseButton};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Direction {
+        Up,
+        Down,
+        Unnamed,
+    }
+
+    struct DirectionNames;
+
+    impl Names for DirectionNames {
+        type Value = Direction;
+
+        fn name(value: Direction) -> Option<Cow<'static, str>> {
+            match value {
+                Direction::Up => Some("Up".into()),
+                Direction::Down => Some("Down".into()),
+                Direction::Unnamed => None,
+            }
+        }
+
+        fn from_name(name: &str) -> Option<Direction> {
+            match name {
+                "Up" => Some(Direction::Up),
+                "Down" => Some(Direction::Down),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Moves {
+        #[serde(
+            serialize_with = "names::serialize::<DirectionNames, _>",
+            deserialize_with = "names::deserialize::<DirectionNames, _>"
+        )]
+        moves: Vec<Direction>,
+    }
+
+    #[test]
+    fn saves_by_name() {
+        let moves = Moves {
+            moves: vec![Direction::Up, Direction::Down],
+        };
+        let ron = ron::to_string(&moves).unwrap();
+        assert_eq!(ron, r#"(moves:["Up","Down"])"#);
+        assert_eq!(ron::from_str::<Moves>(&ron).unwrap(), moves);
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert!(ron::from_str::<Moves>(r#"(moves:["Left"])"#).is_err());
+        let unnamed = Moves {
+            moves: vec![Direction::Unnamed],
+        };
+        assert!(ron::to_string(&unnamed).is_err());
+    }
+
+    #[test]
+    fn serde_names() {
+        assert_eq!(
+            SerdeNames::<KeyCode>::name(KeyCode::Up).as_deref(),
+            Some("Up")
+        );
+        assert_eq!(SerdeNames::<KeyCode>::from_name("Up"), Some(KeyCode::Up));
+        let other = MouseButton::Other(4);
+        let name = SerdeNames::<MouseButton>::name(other).unwrap();
+        assert_eq!(name, "Other(4)");
+        assert_eq!(SerdeNames::<MouseButton>::from_name(&name), Some(other));
+        assert_eq!(SerdeNames::<KeyCode>::from_name("Hyper"), None);
+    }
+}
@@ -0,0 +1,320 @@
+//! Backend agnostic window resize handling.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::{App, CoreStage, Events, IntoSystemDescriptor, Plugin, Resource, World},
+    window::Windows,
+};
+
+/// How the consoles react when the window is resized.
+#[derive(Default, Clone, Copy, Debug)]
+pub enum ResizePolicy {
+    /// Keep the number of cells. The cells are stretched to fill the window. This is the
+    /// default behavior.
+    #[default]
+    KeepCellCount,
+
+    /// Keep the size of the cells in pixels. The consoles grow or shrink to fill the window.
+    KeepCellSize,
+
+    /// Keep the number of cells and their aspect ratio. The consoles are scaled to fit in the
+    /// window, leaving empty bars on the sides.
+    Letterbox,
+
+    /// Keep the number of cells and call the given function, which is free to resize the
+    /// consoles itself.
+    Callback(fn(&mut World, ConsoleResized)),
+}
+
+/// Sent by every backend after the window is resized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsoleResized {
+    /// The previous size of the window in physical pixels.
+    pub previous_window_size: (u32, u32),
+    /// The new size of the window in physical pixels.
+    pub window_size: (u32, u32),
+    /// The size of the console in cells before the window was resized.
+    pub previous_console_size: (u32, u32),
+    /// The size of the console in cells once the resize policy was applied.
+    pub console_size: (u32, u32),
+}
+
+/// Returns the console size keeping cells of `cell_size` logical pixels in a window of
+/// `window_size` logical pixels. See [`ResizePolicy::KeepCellSize`].
+pub fn keep_cell_size(window_size: (f32, f32), cell_size: (f32, f32)) -> (u32, u32) {
+    (
+        ((window_size.0 / cell_size.0.max(1.0)) as u32).max(1),
+        ((window_size.1 / cell_size.1.max(1.0)) as u32).max(1),
+    )
+}
+
+/// The consoles of a backend, resized by the [`ResizePlugin`].
+pub trait ResizeConsoles: Send + Sync + 'static {
+    /// Returns the size in cells of the first console, reported by [`ConsoleResized`].
+    fn console_size(world: &mut World) -> (u32, u32);
+
+    /// Resizes the consoles to the given size in cells, for
+    /// [`ResizePolicy::KeepCellSize`].
+    fn resize_consoles(world: &mut World, console_size: (u32, u32));
+}
+
+/// The resize policy, the size of the cells and the sizes of the window and of the first
+/// console on the previous frame.
+#[derive(Debug, Resource)]
+pub struct ResizeState {
+    policy: ResizePolicy,
+    cell_size: Option<(f32, f32)>,
+    window_size: Option<(u32, u32)>,
+    console_size: (u32, u32),
+    resize_requested: bool,
+}
+
+impl ResizeState {
+    /// Create the state of the given policy.
+    pub fn new(policy: ResizePolicy) -> Self {
+        Self {
+            policy,
+            cell_size: None,
+            window_size: None,
+            console_size: (0, 0),
+            resize_requested: false,
+        }
+    }
+
+    /// Returns the resize policy.
+    pub fn policy(&self) -> ResizePolicy {
+        self.policy
+    }
+
+    /// Returns the size of the cells in logical pixels, kept by
+    /// [`ResizePolicy::KeepCellSize`]. Unless [set](Self::set_cell_size) by the backend, it
+    /// is measured once, on the first frame.
+    pub fn cell_size(&self) -> Option<(f32, f32)> {
+        self.cell_size
+    }
+
+    /// Set the size of the cells in logical pixels, usually the size of the glyphs of the
+    /// font.
+    pub fn set_cell_size(&mut self, cell_size: (u32, u32)) {
+        self.cell_size = Some((cell_size.0 as f32, cell_size.1 as f32));
+    }
+
+    /// Apply the policy again on the next frame, as if the window was resized, for instance
+    /// once the [cell size](Self::set_cell_size) changed with the font.
+    pub fn request_resize(&mut self) {
+        self.resize_requested = true;
+    }
+}
+
+/// Applies the [`ResizePolicy`] when the primary window size changes, then sends a
+/// [`ConsoleResized`] event. Shared by the backends, which provide their consoles with
+/// `C`.
+///
+/// Runs at the start of [`CoreStage::Last`], once the backend systems of the frame sized
+/// the consoles.
+pub struct ResizePlugin<C> {
+    policy: ResizePolicy,
+    consoles: PhantomData<fn() -> C>,
+}
+
+impl<C> ResizePlugin<C> {
+    /// Create the plugin applying the given policy.
+    pub fn new(policy: ResizePolicy) -> Self {
+        Self {
+            policy,
+            consoles: PhantomData,
+        }
+    }
+}
+
+impl<C: ResizeConsoles> Plugin for ResizePlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ConsoleResized>()
+            .insert_resource(ResizeState::new(self.policy))
+            .add_system_to_stage(CoreStage::Last, handle_window_resized::<C>.at_start());
+    }
+}
+
+fn handle_window_resized<C: ResizeConsoles>(world: &mut World) {
+    let (window_size, logical_window_size) = match world.resource::<Windows>().get_primary() {
+        Some(window) => (
+            (window.physical_width(), window.physical_height()),
+            (window.width(), window.height()),
+        ),
+        None => return,
+    };
+    let console_size = C::console_size(world);
+
+    let mut state = world.resource_mut::<ResizeState>();
+    let previous_window_size = *state.window_size.get_or_insert(window_size);
+    let previous_console_size = std::mem::replace(&mut state.console_size, console_size);
+    if state.cell_size.is_none() && console_size.0 > 0 && console_size.1 > 0 {
+        state.cell_size = Some((
+            logical_window_size.0 / console_size.0 as f32,
+            logical_window_size.1 / console_size.1 as f32,
+        ));
+    }
+    let resize_requested = std::mem::take(&mut state.resize_requested);
+    if previous_window_size == window_size && !resize_requested {
+        return;
+    }
+    state.window_size = Some(window_size);
+
+    let policy = state.policy;
+    if let (ResizePolicy::KeepCellSize, Some(cell_size)) = (policy, state.cell_size) {
+        C::resize_consoles(world, keep_cell_size(logical_window_size, cell_size));
+    }
+
+    let console_size = C::console_size(world);
+    world.resource_mut::<ResizeState>().console_size = console_size;
+    let resized = ConsoleResized {
+        previous_window_size,
+        window_size,
+        previous_console_size,
+        console_size,
+    };
+    world.resource_mut::<Events<ConsoleResized>>().send(resized);
+
+    if let ResizePolicy::Callback(callback) = policy {
+        callback(world, resized);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::window::{Window, WindowDescriptor, WindowId};
+
+    use super::*;
+
+    #[test]
+    fn keeps_the_cell_size() {
+        assert_eq!(keep_cell_size((800.0, 600.0), (8.0, 12.0)), (100, 50));
+        assert_eq!(keep_cell_size((807.0, 611.0), (8.0, 12.0)), (100, 50));
+        assert_eq!(keep_cell_size((4.0, 4.0), (8.0, 12.0)), (1, 1));
+        assert_eq!(keep_cell_size((100.0, 100.0), (0.0, 0.0)), (100, 100));
+    }
+
+    #[derive(Resource)]
+    struct Consoles((u32, u32));
+
+    impl ResizeConsoles for Consoles {
+        fn console_size(world: &mut World) -> (u32, u32) {
+            world.resource::<Consoles>().0
+        }
+
+        fn resize_consoles(world: &mut World, console_size: (u32, u32)) {
+            world.resource_mut::<Consoles>().0 = console_size;
+        }
+    }
+
+    fn app(policy: ResizePolicy, scale_factor: f64) -> App {
+        let mut windows = Windows::default();
+        windows.add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            (800.0 * scale_factor) as u32,
+            (600.0 * scale_factor) as u32,
+            scale_factor,
+            None,
+            None,
+        ));
+        let mut app = App::new();
+        app.insert_resource(windows)
+            .insert_resource(Consoles((80, 50)))
+            .add_plugin(ResizePlugin::<Consoles>::new(policy));
+        app
+    }
+
+    fn resize(app: &mut App, width: u32, height: u32) -> Option<ConsoleResized> {
+        let mut windows = app.world.resource_mut::<Windows>();
+        let window = windows.get_primary_mut().unwrap();
+        let scale_factor = window.scale_factor();
+        window.update_actual_size_from_backend(
+            (width as f64 * scale_factor) as u32,
+            (height as f64 * scale_factor) as u32,
+        );
+        app.update();
+        let events = app.world.resource::<Events<ConsoleResized>>();
+        events.iter_current_update_events().last().copied()
+    }
+
+    #[test]
+    fn keep_cell_size_does_not_drift() {
+        let mut app = app(ResizePolicy::KeepCellSize, 1.0);
+        app.update();
+        assert_eq!(
+            app.world.resource::<ResizeState>().cell_size(),
+            Some((10.0, 12.0))
+        );
+
+        for (width, height) in [(805, 610), (813, 621), (827, 633), (839, 647)] {
+            resize(&mut app, width, height).unwrap();
+        }
+        assert_eq!(app.world.resource::<Consoles>().0, (83, 53));
+
+        let resized = resize(&mut app, 800, 600).unwrap();
+        assert_eq!(resized.previous_console_size, (83, 53));
+        assert_eq!(resized.console_size, (80, 50));
+        assert_eq!(resized.window_size, (800, 600));
+    }
+
+    #[test]
+    fn keep_cell_size_uses_logical_pixels() {
+        let mut app = app(ResizePolicy::KeepCellSize, 2.0);
+        app.world
+            .resource_mut::<ResizeState>()
+            .set_cell_size((8, 8));
+        app.update();
+
+        let resized = resize(&mut app, 400, 320).unwrap();
+        assert_eq!(resized.window_size, (800, 640));
+        assert_eq!(resized.console_size, (50, 40));
+    }
+
+    #[test]
+    fn requested_resize() {
+        let mut app = app(ResizePolicy::KeepCellSize, 1.0);
+        app.update();
+        assert_eq!(resize(&mut app, 800, 600), None);
+
+        let mut state = app.world.resource_mut::<ResizeState>();
+        state.set_cell_size((16, 12));
+        state.request_resize();
+        let resized = resize(&mut app, 800, 600).unwrap();
+        assert_eq!(resized.window_size, (800, 600));
+        assert_eq!(resized.previous_window_size, (800, 600));
+        assert_eq!(resized.console_size, (50, 50));
+        assert_eq!(resize(&mut app, 800, 600), None);
+    }
+
+    #[test]
+    fn keep_cell_count() {
+        let mut app = app(ResizePolicy::KeepCellCount, 1.0);
+        app.update();
+        assert_eq!(resize(&mut app, 800, 600), None);
+
+        let resized = resize(&mut app, 1024, 768).unwrap();
+        assert_eq!(
+            resized,
+            ConsoleResized {
+                previous_window_size: (800, 600),
+                window_size: (1024, 768),
+                previous_console_size: (80, 50),
+                console_size: (80, 50),
+            }
+        );
+    }
+
+    #[test]
+    fn callback() {
+        fn halve(world: &mut World, resized: ConsoleResized) {
+            world.resource_mut::<Consoles>().0 = (resized.console_size.0 / 2, 1);
+        }
+
+        let mut app = app(ResizePolicy::Callback(halve), 1.0);
+        app.update();
+        resize(&mut app, 640, 480).unwrap();
+        assert_eq!(app.world.resource::<Consoles>().0, (40, 1));
+    }
+}
@@ -0,0 +1,309 @@
+//! Golden-file snapshots of console contents.
+
+use std::{
+    fmt::{self, Write as _},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{ConsoleApi, Rgba};
+
+/// Set this environment variable to `1` to overwrite the golden files with the current
+/// console contents instead of comparing them.
+pub const BLESS_ENV_VAR: &str = "BRLTK_BLESS";
+
+/// Section headers of the snapshot format.
+const SECTIONS: [&str; 4] = ["[glyphs]", "[fore]", "[back]", "[palette]"];
+
+/// Symbol used for colors that could not be read back from the console.
+const UNKNOWN_COLOR: char = '-';
+
+/// Symbols used for the colors of a snapshot, in order of first appearance.
+const PALETTE_SYMBOLS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The glyphs and colors of a console, rendered to a stable text format.
+///
+/// The format lists the glyphs row by row, then the foreground and background colors as
+/// grids of palette symbols, then the palette itself:
+///
+/// ```text
+/// size 3x1
+/// [glyphs]
+/// @..
+/// [fore]
+/// acc
+/// [back]
+/// bbb
+/// [palette]
+/// a #ffff00ff
+/// b #000000ff
+/// c #808080ff
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleSnapshot(String);
+
+impl ConsoleSnapshot {
+    /// Take a snapshot of the given console.
+    pub fn capture<C: ConsoleApi + ?Sized>(console: &C) -> Self {
+        let (width, height) = console.size();
+        let mut palette: Vec<Rgba> = Vec::new();
+        let mut symbol = |color: Option<Rgba>| match color {
+            Some(color) => {
+                let index = palette.iter().position(|&c| c == color).unwrap_or_else(|| {
+                    palette.push(color);
+                    palette.len() - 1
+                });
+                palette_symbol(index)
+            }
+            None => UNKNOWN_COLOR,
+        };
+
+        let mut glyphs = String::new();
+        let mut fores = String::new();
+        let mut backs = String::new();
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                glyphs.push(match console.get_glyph(x, y) {
+                    Some(glyph) if !glyph.is_control() => glyph,
+                    Some(_) => char::REPLACEMENT_CHARACTER,
+                    None => ' ',
+                });
+                fores.push(symbol(console.get_fore(x, y)));
+                backs.push(symbol(console.get_back(x, y)));
+            }
+            glyphs.push('\n');
+            fores.push('\n');
+            backs.push('\n');
+        }
+
+        let mut text = format!("size {width}x{height}\n");
+        for (header, grid) in SECTIONS.iter().zip([&glyphs, &fores, &backs]) {
+            text.push_str(header);
+            text.push('\n');
+            text.push_str(grid);
+        }
+        text.push_str(SECTIONS[3]);
+        text.push('\n');
+        for (index, (r, g, b, a)) in palette.into_iter().enumerate() {
+            let _ = writeln!(
+                text,
+                "{} #{r:02x}{g:02x}{b:02x}{a:02x}",
+                palette_symbol(index)
+            );
+        }
+
+        Self(text)
+    }
+
+    /// Returns the snapshot text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Compares the snapshot with the golden file at `path`.
+    ///
+    /// When the [`BLESS_ENV_VAR`] environment variable is set to `1`, the golden file is
+    /// written instead. Returns a readable diff when the snapshot does not match.
+    pub fn check(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        self.check_or_bless(
+            path.as_ref(),
+            std::env::var(BLESS_ENV_VAR).as_deref() == Ok("1"),
+        )
+    }
+
+    fn check_or_bless(&self, path: &Path, bless: bool) -> Result<(), String> {
+        if bless {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("cannot create {}: {e}", parent.display()))?;
+            }
+            return fs::write(path, &self.0)
+                .map_err(|e| format!("cannot write {}: {e}", path.display()));
+        }
+
+        let expected = fs::read_to_string(path).map_err(|e| {
+            format!(
+                "cannot read golden file {}: {e}\nrun with {BLESS_ENV_VAR}=1 to create it",
+                path.display()
+            )
+        })?;
+
+        // Golden files may have been checked out with Windows line endings.
+        let expected = expected.replace("\r\n", "\n");
+        if expected == self.0 {
+            return Ok(());
+        }
+
+        Err(format!(
+            "console snapshot does not match {}\n{}run with {BLESS_ENV_VAR}=1 to update it",
+            path.display(),
+            diff(&expected, &self.0)
+        ))
+    }
+}
+
+impl fmt::Display for ConsoleSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Returns the path of the golden file named `name`, in the `tests/snapshots` folder of the
+/// crate being tested.
+pub fn snapshot_path(name: &str) -> PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    Path::new(&manifest_dir)
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.snap"))
+}
+
+/// Asserts that the console matches the golden file named `name`, in the `tests/snapshots`
+/// folder of the crate being tested. See [`ConsoleSnapshot::check`].
+///
+/// # Panics
+///
+/// Panics with a diff of the snapshot if it does not match the golden file.
+#[track_caller]
+pub fn assert_console_snapshot<C: ConsoleApi + ?Sized>(name: &str, console: &C) {
+    if let Err(message) = ConsoleSnapshot::capture(console).check(snapshot_path(name)) {
+        panic!("{message}");
+    }
+}
+
+fn palette_symbol(index: usize) -> char {
+    PALETTE_SYMBOLS
+        .chars()
+        .nth(index)
+        .or_else(|| char::from_u32(0xC0 + (index - PALETTE_SYMBOLS.len()) as u32))
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Line based diff, keeping the name of the current section for context.
+///
+/// The lines are matched along their longest common subsequence, so that an inserted or
+/// removed row is reported once instead of shifting every following row.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // `common[e][a]` is the length of the longest common subsequence of `expected[e..]`
+    // and `actual[a..]`.
+    let mut common = vec![vec![0_usize; actual.len() + 1]; expected.len() + 1];
+    for e in (0..expected.len()).rev() {
+        for a in (0..actual.len()).rev() {
+            common[e][a] = if expected[e] == actual[a] {
+                common[e + 1][a + 1] + 1
+            } else {
+                common[e + 1][a].max(common[e][a + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let mut section = "";
+    let (mut e, mut a) = (0, 0);
+    while e < expected.len() || a < actual.len() {
+        if e < expected.len() && a < actual.len() && expected[e] == actual[a] {
+            if SECTIONS.contains(&actual[a]) {
+                section = actual[a];
+            }
+            e += 1;
+            a += 1;
+        } else if e < expected.len() && (a == actual.len() || common[e + 1][a] >= common[e][a + 1])
+        {
+            let _ = writeln!(out, "- line {} {section}: {}", e + 1, expected[e]);
+            e += 1;
+        } else {
+            if SECTIONS.contains(&actual[a]) {
+                section = actual[a];
+            }
+            let _ = writeln!(out, "+ line {} {section}: {}", a + 1, actual[a]);
+            a += 1;
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellBuffer, TextAlign};
+
+    const YELLOW: Rgba = (255, 255, 0, 255);
+    const GRAY: Rgba = (128, 128, 128, 255);
+
+    fn draw_panel(console: &mut dyn ConsoleApi) {
+        console.clear(Some(GRAY), Some((0, 0, 64, 255)), Some('.'));
+        console.print(1, 0, "HP 12", TextAlign::Left, Some(YELLOW), None);
+        console.print(4, 2, "log", TextAlign::Center, None, Some((64, 0, 0, 255)));
+        console.put_glyph(7, 3, '@');
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("brltk_snapshot_{}", std::process::id()))
+            .join(format!("{name}.snap"))
+    }
+
+    #[test]
+    fn capture_format() {
+        let mut console = CellBuffer::new(3, 1);
+        console.clear(Some(GRAY), None, Some('.'));
+        console.put_cell(0, 0, Some('@'), Some(YELLOW), None);
+
+        assert_eq!(
+            ConsoleSnapshot::capture(&console).as_str(),
+            "size 3x1\n[glyphs]\n@..\n[fore]\nacc\n[back]\nbbb\n[palette]\na #ffff00ff\nb \
+             #000000ff\nc #808080ff\n"
+        );
+    }
+
+    #[test]
+    fn cell_buffer_matches_golden_file() {
+        let mut console = CellBuffer::new(9, 4);
+        draw_panel(&mut console);
+        assert_console_snapshot("cell_buffer_panel", &console);
+    }
+
+    #[test]
+    fn mirrored_console_matches_golden_file() {
+        let mut console = CellBuffer::new(9, 4);
+        let mut mirror = CellBuffer::new(0, 0);
+        draw_panel(&mut mirror.mirror(&mut console));
+
+        assert_eq!(mirror, console);
+        assert_console_snapshot("cell_buffer_panel", &mirror);
+    }
+
+    #[test]
+    fn bless_then_check() {
+        let path = temp_path("bless_then_check");
+        let mut console = CellBuffer::new(9, 4);
+        draw_panel(&mut console);
+        let snapshot = ConsoleSnapshot::capture(&console);
+
+        assert!(snapshot.check_or_bless(&path, false).is_err());
+        snapshot.check_or_bless(&path, true).unwrap();
+        snapshot.check_or_bless(&path, false).unwrap();
+
+        console.put_glyph(0, 1, '#');
+        let message = ConsoleSnapshot::capture(&console)
+            .check_or_bless(&path, false)
+            .unwrap_err();
+        assert!(message.contains("- line 4 [glyphs]: .........\n+ line 4 [glyphs]: #........\n"));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn diff_reports_an_inserted_row_once() {
+        let expected = "size 3x3\n[glyphs]\nabc\ndef\nghi\n";
+        let actual = "size 3x4\n[glyphs]\nabc\nxyz\ndef\nghi\n";
+
+        assert_eq!(
+            diff(expected, actual),
+            "- line 1 : size 3x3\n+ line 1 : size 3x4\n+ line 4 [glyphs]: xyz\n"
+        );
+    }
+}
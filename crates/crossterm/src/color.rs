@@ -0,0 +1,123 @@
+//! Conversion of the console colors to terminal colors.
+
+use brltk_common::Rgba;
+use crossterm::style::Color;
+
+/// The colors the terminal is able to display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Use 24-bit colors if the `COLORTERM` environment variable is `truecolor` or `24bit`,
+    /// 256 colors otherwise. This is the default behavior.
+    #[default]
+    Auto,
+    /// Use 24-bit colors.
+    TrueColor,
+    /// Use the closest color of the standard 256 colors palette.
+    Ansi256,
+}
+
+impl ColorMode {
+    /// Returns the mode to use, detecting the terminal support for [`ColorMode::Auto`].
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => match std::env::var("COLORTERM").as_deref() {
+                Ok("truecolor" | "24bit") => Self::TrueColor,
+                _ => Self::Ansi256,
+            },
+            mode => mode,
+        }
+    }
+
+    /// Converts a console color to a terminal color. The alpha channel is ignored.
+    pub(crate) fn to_color(self, (r, g, b, _): Rgba) -> Color {
+        match self {
+            Self::Ansi256 => Color::AnsiValue(ansi256(r, g, b)),
+            Self::Auto | Self::TrueColor => Color::Rgb { r, g, b },
+        }
+    }
+}
+
+/// The levels of each channel in the 6x6x6 color cube of the xterm palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Returns the closest color of the 256 colors palette: the nearest color of the 6x6x6
+/// color cube, or of the 24 grays ramp from 8 to 238, whichever is nearer. The 16 system
+/// colors are skipped, their values depend on the terminal.
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // The nearest cube level, the midpoints between the levels being 48, 115, 155, ...
+    let level = |c: u8| match c {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (c - 35) / 40,
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = [ri, gi, bi].map(|i| CUBE_LEVELS[usize::from(i)]);
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_index;
+
+    let distance = |[cr, cg, cb]: [u8; 3]| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+    if distance([gray; 3]) < distance(cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri + 6 * gi + bi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube_colors() {
+        assert_eq!(ansi256(0, 0, 0), 16);
+        assert_eq!(ansi256(255, 255, 255), 231);
+        assert_eq!(ansi256(255, 0, 0), 196);
+        assert_eq!(ansi256(95, 135, 175), 67);
+        assert_eq!(ansi256(0, 215, 95), 41);
+    }
+
+    #[test]
+    fn nearest_cube_level() {
+        // 114 is nearer to 95 than to 135, 115 is not nearer to 95.
+        assert_eq!(ansi256(114, 0, 255), 16 + 36 + 5);
+        assert_eq!(ansi256(115, 0, 255), 16 + 72 + 5);
+        assert_eq!(ansi256(47, 0, 255), 16 + 5);
+        assert_eq!(ansi256(48, 0, 255), 16 + 36 + 5);
+        assert_eq!(ansi256(200, 0, 255), 16 + 144 + 5);
+    }
+
+    #[test]
+    fn grays() {
+        assert_eq!(ansi256(8, 8, 8), 232);
+        assert_eq!(ansi256(128, 128, 128), 244);
+        assert_eq!(ansi256(238, 238, 238), 255);
+        // Nearer to the gray 98 than to the cube gray 95.
+        assert_eq!(ansi256(100, 100, 100), 241);
+        // The cube has exact grays too.
+        assert_eq!(ansi256(95, 95, 95), 59);
+        assert_eq!(ansi256(250, 250, 250), 231);
+    }
+
+    #[test]
+    fn near_grays() {
+        assert_eq!(ansi256(130, 128, 126), 244);
+        assert_eq!(ansi256(60, 58, 62), 237);
+    }
+
+    #[test]
+    fn true_color() {
+        assert_eq!(
+            ColorMode::TrueColor.to_color((1, 2, 3, 0)),
+            Color::Rgb { r: 1, g: 2, b: 3 }
+        );
+        assert_eq!(
+            ColorMode::Ansi256.to_color((255, 0, 0, 255)),
+            Color::AnsiValue(196)
+        );
+    }
+}
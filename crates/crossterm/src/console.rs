@@ -0,0 +1,37 @@
+//! Console drawn into the terminal.
+
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::Resource;
+use brltk_common::CellBuffer;
+
+/// The root console of the crossterm backend, drawn into the terminal at the end of every
+/// frame. Draw on it through the [`ConsoleApi`](brltk_common::ConsoleApi) trait.
+///
+/// Only the cells that changed since the previous frame are sent to the terminal. Cells
+/// outside of the terminal are not drawn, and the alpha channel of the colors is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct CrosstermConsole(pub CellBuffer);
+
+impl CrosstermConsole {
+    /// Create a console filled with default cells.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self(CellBuffer::new(width, height))
+    }
+}
+
+impl Deref for CrosstermConsole {
+    type Target = CellBuffer;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for CrosstermConsole {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,295 @@
+//! Keyboard and mouse input read from the terminal.
+
+use bevy::{
+    app::AppExit,
+    prelude::{Events, KeyCode, MouseButton, ResMut, Resource},
+};
+use brltk_common::{AppLifecycle, ConsoleApi, ConsoleInput, ConsoleResized};
+use crossterm::event::{
+    Event, KeyCode as TermKeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton as TermButton,
+    MouseEvent, MouseEventKind,
+};
+
+use crate::CrosstermConsole;
+
+/// The terminal events read by the runner since the previous frame.
+#[derive(Default, Debug, Resource)]
+pub(crate) struct TerminalInput {
+    pub(crate) events: Vec<Event>,
+    /// The terminal reports key releases, see the
+    /// [kitty keyboard protocol](https://sw.kovidgoyal.net/kitty/keyboard-protocol/).
+    pub(crate) reports_releases: bool,
+    /// The size of the terminal in cells.
+    pub(crate) terminal_size: (u32, u32),
+    /// The console follows the size of the terminal.
+    pub(crate) follow_terminal_size: bool,
+    pub(crate) exit_on_ctrl_c: bool,
+    /// The keys to release on the next frame, when the terminal does not report releases.
+    pub(crate) pressed_last_frame: Vec<KeyCode>,
+}
+
+/// Reports the [`TerminalInput`] events to the [`ConsoleInput`].
+pub(crate) fn apply_terminal_input(
+    mut terminal_input: ResMut<TerminalInput>,
+    mut input: ResMut<ConsoleInput>,
+    mut console: ResMut<CrosstermConsole>,
+    mut resized_events: ResMut<Events<ConsoleResized>>,
+    mut app_exit_events: ResMut<Events<AppExit>>,
+    mut lifecycle_events: Option<ResMut<Events<AppLifecycle>>>,
+) {
+    let terminal_input = &mut *terminal_input;
+    input.on_frame();
+
+    let mut pressed = Vec::new();
+    for event in terminal_input.events.drain(..) {
+        match event {
+            Event::Key(key_event) => {
+                if is_ctrl_c(&key_event) {
+                    input.request_close();
+                    if terminal_input.exit_on_ctrl_c {
+                        app_exit_events.send(AppExit);
+                    }
+                    continue;
+                }
+                apply_key(
+                    &mut input,
+                    &key_event,
+                    terminal_input.reports_releases,
+                    &mut pressed,
+                );
+            }
+            Event::Mouse(mouse_event) => apply_mouse(&mut input, &mouse_event),
+            Event::Resize(width, height) => {
+                let terminal_size = (u32::from(width), u32::from(height));
+                let previous_window_size =
+                    std::mem::replace(&mut terminal_input.terminal_size, terminal_size);
+                let previous_console_size = (console.width(), console.height());
+                if terminal_input.follow_terminal_size {
+                    console.resize(terminal_size.0, terminal_size.1);
+                }
+                resized_events.send(ConsoleResized {
+                    previous_window_size,
+                    window_size: terminal_size,
+                    previous_console_size,
+                    console_size: (console.width(), console.height()),
+                });
+            }
+            Event::FocusGained | Event::FocusLost => {
+                if let Some(lifecycle_events) = &mut lifecycle_events {
+                    lifecycle_events.send(if event == Event::FocusGained {
+                        AppLifecycle::FocusGained
+                    } else {
+                        AppLifecycle::FocusLost
+                    });
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => (),
+        }
+    }
+
+    // Without release events, a key is down for the frames it is pressed in.
+    if !terminal_input.reports_releases {
+        for key in terminal_input.pressed_last_frame.drain(..) {
+            if !pressed.contains(&key) {
+                input.release_key(key);
+            }
+        }
+        terminal_input.pressed_last_frame = pressed;
+    }
+}
+
+fn is_ctrl_c(key_event: &KeyEvent) -> bool {
+    key_event.kind == KeyEventKind::Press
+        && key_event.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key_event.code, TermKeyCode::Char('c' | 'C'))
+}
+
+fn apply_key(
+    input: &mut ConsoleInput,
+    key_event: &KeyEvent,
+    reports_releases: bool,
+    pressed: &mut Vec<KeyCode>,
+) {
+    // The modifiers are only reported along with the other keys.
+    for (modifier, key) in [
+        (KeyModifiers::SHIFT, KeyCode::LShift),
+        (KeyModifiers::CONTROL, KeyCode::LControl),
+        (KeyModifiers::ALT, KeyCode::LAlt),
+    ] {
+        let down = key_event.modifiers.contains(modifier)
+            || (key == KeyCode::LShift && key_event.code == TermKeyCode::BackTab);
+        if down {
+            if !input.key(key) {
+                input.press_key(key);
+            }
+            pressed.push(key);
+        } else if reports_releases && input.key(key) {
+            input.release_key(key);
+        }
+    }
+
+    if key_event.kind != KeyEventKind::Release {
+        if let TermKeyCode::Char(c) = key_event.code {
+            if !key_event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            {
+                input.push_text(c.encode_utf8(&mut [0; 4]));
+            }
+        }
+    }
+
+    let Some(key) = to_key_code(key_event.code) else {
+        return;
+    };
+    match key_event.kind {
+        KeyEventKind::Press | KeyEventKind::Repeat => {
+            input.press_key(key);
+            pressed.push(key);
+        }
+        KeyEventKind::Release => input.release_key(key),
+    }
+}
+
+fn apply_mouse(input: &mut ConsoleInput, mouse_event: &MouseEvent) {
+    input.set_mouse_position((f32::from(mouse_event.column), f32::from(mouse_event.row)));
+    match mouse_event.kind {
+        MouseEventKind::Down(button) => input.press_mouse_button(to_mouse_button(button)),
+        MouseEventKind::Up(button) => input.release_mouse_button(to_mouse_button(button)),
+        MouseEventKind::Drag(_)
+        | MouseEventKind::Moved
+        | MouseEventKind::ScrollDown
+        | MouseEventKind::ScrollUp => (),
+    }
+}
+
+fn to_mouse_button(button: TermButton) -> MouseButton {
+    match button {
+        TermButton::Left => MouseButton::Left,
+        TermButton::Right => MouseButton::Right,
+        TermButton::Middle => MouseButton::Middle,
+    }
+}
+
+fn to_key_code(code: TermKeyCode) -> Option<KeyCode> {
+    Some(match code {
+        TermKeyCode::Backspace => KeyCode::Back,
+        TermKeyCode::Enter => KeyCode::Return,
+        TermKeyCode::Left => KeyCode::Left,
+        TermKeyCode::Right => KeyCode::Right,
+        TermKeyCode::Up => KeyCode::Up,
+        TermKeyCode::Down => KeyCode::Down,
+        TermKeyCode::Home => KeyCode::Home,
+        TermKeyCode::End => KeyCode::End,
+        TermKeyCode::PageUp => KeyCode::PageUp,
+        TermKeyCode::PageDown => KeyCode::PageDown,
+        TermKeyCode::Tab | TermKeyCode::BackTab => KeyCode::Tab,
+        TermKeyCode::Delete => KeyCode::Delete,
+        TermKeyCode::Insert => KeyCode::Insert,
+        TermKeyCode::Esc => KeyCode::Escape,
+        TermKeyCode::F(n) => match n {
+            1 => KeyCode::F1,
+            2 => KeyCode::F2,
+            3 => KeyCode::F3,
+            4 => KeyCode::F4,
+            5 => KeyCode::F5,
+            6 => KeyCode::F6,
+            7 => KeyCode::F7,
+            8 => KeyCode::F8,
+            9 => KeyCode::F9,
+            10 => KeyCode::F10,
+            11 => KeyCode::F11,
+            12 => KeyCode::F12,
+            _ => return None,
+        },
+        TermKeyCode::Char(c) => return char_key_code(c),
+        _ => return None,
+    })
+}
+
+fn char_key_code(c: char) -> Option<KeyCode> {
+    Some(match c.to_ascii_lowercase() {
+        'a' => KeyCode::A,
+        'b' => KeyCode::B,
+        'c' => KeyCode::C,
+        'd' => KeyCode::D,
+        'e' => KeyCode::E,
+        'f' => KeyCode::F,
+        'g' => KeyCode::G,
+        'h' => KeyCode::H,
+        'i' => KeyCode::I,
+        'j' => KeyCode::J,
+        'k' => KeyCode::K,
+        'l' => KeyCode::L,
+        'm' => KeyCode::M,
+        'n' => KeyCode::N,
+        'o' => KeyCode::O,
+        'p' => KeyCode::P,
+        'q' => KeyCode::Q,
+        'r' => KeyCode::R,
+        's' => KeyCode::S,
+        't' => KeyCode::T,
+        'u' => KeyCode::U,
+        'v' => KeyCode::V,
+        'w' => KeyCode::W,
+        'x' => KeyCode::X,
+        'y' => KeyCode::Y,
+        'z' => KeyCode::Z,
+        '0' => KeyCode::Key0,
+        '1' => KeyCode::Key1,
+        '2' => KeyCode::Key2,
+        '3' => KeyCode::Key3,
+        '4' => KeyCode::Key4,
+        '5' => KeyCode::Key5,
+        '6' => KeyCode::Key6,
+        '7' => KeyCode::Key7,
+        '8' => KeyCode::Key8,
+        '9' => KeyCode::Key9,
+        ' ' => KeyCode::Space,
+        '-' => KeyCode::Minus,
+        '=' => KeyCode::Equals,
+        ',' => KeyCode::Comma,
+        '.' => KeyCode::Period,
+        '/' => KeyCode::Slash,
+        ';' => KeyCode::Semicolon,
+        '\'' => KeyCode::Apostrophe,
+        '[' => KeyCode::LBracket,
+        ']' => KeyCode::RBracket,
+        '\\' => KeyCode::Backslash,
+        '`' => KeyCode::Grave,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_keys() {
+        assert_eq!(to_key_code(TermKeyCode::Enter), Some(KeyCode::Return));
+        assert_eq!(to_key_code(TermKeyCode::Backspace), Some(KeyCode::Back));
+        assert_eq!(to_key_code(TermKeyCode::Esc), Some(KeyCode::Escape));
+        assert_eq!(to_key_code(TermKeyCode::BackTab), Some(KeyCode::Tab));
+        assert_eq!(to_key_code(TermKeyCode::PageDown), Some(KeyCode::PageDown));
+    }
+
+    #[test]
+    fn function_keys() {
+        assert_eq!(to_key_code(TermKeyCode::F(1)), Some(KeyCode::F1));
+        assert_eq!(to_key_code(TermKeyCode::F(12)), Some(KeyCode::F12));
+        assert_eq!(to_key_code(TermKeyCode::F(13)), None);
+    }
+
+    #[test]
+    fn characters() {
+        assert_eq!(to_key_code(TermKeyCode::Char('a')), Some(KeyCode::A));
+        assert_eq!(to_key_code(TermKeyCode::Char('Z')), Some(KeyCode::Z));
+        assert_eq!(to_key_code(TermKeyCode::Char('7')), Some(KeyCode::Key7));
+        assert_eq!(to_key_code(TermKeyCode::Char(' ')), Some(KeyCode::Space));
+        assert_eq!(to_key_code(TermKeyCode::Char('`')), Some(KeyCode::Grave));
+        assert_eq!(to_key_code(TermKeyCode::Char('é')), None);
+        assert_eq!(to_key_code(TermKeyCode::Null), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! `brltk_crossterm` is a backend drawing the console into the terminal emulator running the
+//! game, using [crossterm].
+//!
+//! The console is drawn with ANSI escape sequences, in 24-bit colors or with the closest
+//! colors of the 256 colors palette, see [`ColorMode`]. Keyboard and mouse input is read from
+//! the terminal into the [`ConsoleInput`](brltk_common::ConsoleInput) resource.
+//!
+//! [crossterm]: https://github.com/crossterm-rs/crossterm
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::time::Duration;
+
+use bevy::prelude::{App as BevyApp, CoreStage, IntoSystemDescriptor, World};
+use brltk_common::{
+    Backend, BackendCapabilities, ConsoleInput, ConsoleInputSystem, ConsoleResized,
+};
+
+mod color;
+mod console;
+mod input;
+mod runner;
+
+use crate::input::{apply_terminal_input, TerminalInput};
+pub use crate::{color::*, console::*, runner::*};
+
+/// The crossterm backend.
+#[derive(Debug, Clone)]
+pub struct CrosstermBackend {
+    /// The size of the [`CrosstermConsole`] in cells. `None` uses the size of the terminal,
+    /// and resizes the console along with the terminal.
+    pub console_size: Option<(u32, u32)>,
+
+    /// The duration of a frame.
+    pub frame_time: Duration,
+
+    /// The colors used to draw the console.
+    pub color_mode: ColorMode,
+
+    /// Read the mouse events from the terminal.
+    pub mouse: bool,
+
+    /// Send an [`AppExit`](bevy::app::AppExit) event when `Ctrl+C` is pressed. Since the
+    /// terminal is in raw mode, `Ctrl+C` does not interrupt the process.
+    pub exit_on_ctrl_c: bool,
+}
+
+impl Default for CrosstermBackend {
+    fn default() -> Self {
+        Self {
+            console_size: None,
+            frame_time: Duration::from_secs(1) / 30,
+            color_mode: ColorMode::Auto,
+            mouse: true,
+            exit_on_ctrl_c: true,
+        }
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn build(&self, app: &mut BevyApp) {
+        let terminal_size = crossterm::terminal::size().map_or((80, 24), |(width, height)| {
+            (u32::from(width), u32::from(height))
+        });
+        let (console_width, console_height) = self.console_size.unwrap_or(terminal_size);
+
+        app.insert_resource(CrosstermConsole::new(console_width, console_height))
+            .init_resource::<ConsoleInput>()
+            .add_event::<ConsoleResized>()
+            .insert_resource(TerminalInput {
+                terminal_size,
+                follow_terminal_size: self.console_size.is_none(),
+                exit_on_ctrl_c: self.exit_on_ctrl_c,
+                ..Default::default()
+            })
+            .add_system_to_stage(
+                CoreStage::First,
+                apply_terminal_input.label(ConsoleInputSystem),
+            );
+
+        let Self {
+            frame_time,
+            color_mode,
+            mouse,
+            ..
+        } = self.clone();
+
+        app.set_runner(move |app| crossterm_runner(app, frame_time, color_mode, mouse));
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            name: "crossterm",
+            subcell: false,
+            alpha_blending: false,
+            true_color: self.color_mode.resolve() == ColorMode::TrueColor,
+            named_colors: false,
+            layers: false,
+            sparse_layers: false,
+            multiple_fonts: false,
+            runtime_font_change: false,
+            runtime_resize: self.console_size.is_none(),
+            borders: false,
+            windowed: false,
+        }
+    }
+
+    /// Restores the terminal at the end of the last frame, so it is usable again by the time
+    /// the app exits.
+    fn cleanup(&self, world: &mut World) {
+        runner::restore_terminal(world);
+    }
+}
@@ -0,0 +1,242 @@
+//! Runner drawing the console into the terminal.
+
+use std::{
+    io::{self, BufWriter, Stdout, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use bevy::{
+    app::{App, AppExit},
+    ecs::{
+        event::{Events, ManualEventReader},
+        world::World,
+    },
+    utils::Instant,
+};
+use brltk_common::{Cell, CellBuffer, ConsoleApi, Rgba};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    queue,
+    style::{Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{
+        self, supports_keyboard_enhancement, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+
+use crate::{input::TerminalInput, ColorMode, CrosstermConsole};
+
+/// Runs the app in the terminal, one frame every `frame_time`.
+///
+/// The terminal is switched to raw mode and to the alternate screen until the last frame,
+/// once an [`AppExit`] event is sent: the [`CrosstermBackend`](crate::CrosstermBackend)
+/// restores it during its [`cleanup`](brltk_common::Backend::cleanup), or the runner when it
+/// stops. The terminal is restored on panics too. The
+/// terminal events are read before every frame, and the cells of the [`CrosstermConsole`]
+/// that changed are drawn after it.
+pub fn crossterm_runner(mut app: App, frame_time: Duration, color_mode: ColorMode, mouse: bool) {
+    let result = run(&mut app, frame_time, color_mode.resolve(), mouse);
+    restore_terminal(&mut app.world);
+    if let Err(error) = result {
+        eprintln!("crossterm runner error: {error}");
+    }
+}
+
+/// Leaves the alternate screen and the raw mode, if the runner entered them and they were
+/// not restored yet.
+pub(crate) fn restore_terminal(world: &mut World) {
+    drop(world.remove_non_send_resource::<TerminalGuard>());
+}
+
+fn run(app: &mut App, frame_time: Duration, color_mode: ColorMode, mouse: bool) -> io::Result<()> {
+    let mut stdout = BufWriter::new(io::stdout());
+    let guard = TerminalGuard::enter(&mut stdout, mouse)?;
+    app.world.resource_mut::<TerminalInput>().reports_releases = guard.keyboard_enhancement;
+    app.world.insert_non_send_resource(guard);
+
+    let mut app_exit_event_reader = ManualEventReader::<AppExit>::default();
+    let mut previous: Option<CellBuffer> = None;
+
+    loop {
+        let start = Instant::now();
+
+        let mut events = Vec::new();
+        while event::poll(Duration::ZERO)? {
+            events.push(event::read()?);
+        }
+        if events
+            .iter()
+            .any(|event| matches!(event, event::Event::Resize(..)))
+        {
+            previous = None;
+        }
+        app.world
+            .resource_mut::<TerminalInput>()
+            .events
+            .extend(events);
+
+        app.update();
+
+        if let Some(app_exit_events) = app.world.get_resource::<Events<AppExit>>() {
+            if app_exit_event_reader.iter(app_exit_events).last().is_some() {
+                break;
+            }
+        }
+
+        let console = &app.world.resource::<CrosstermConsole>().0;
+        draw(&mut stdout, console, previous.as_ref(), color_mode)?;
+        previous = Some(console.clone());
+
+        if let Some(remaining) = frame_time.checked_sub(start.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws the cells that changed since the `previous` frame, or the whole console if there
+/// is no previous frame.
+fn draw(
+    stdout: &mut BufWriter<Stdout>,
+    console: &CellBuffer,
+    previous: Option<&CellBuffer>,
+    color_mode: ColorMode,
+) -> io::Result<()> {
+    let previous = previous.filter(|previous| previous.size() == console.size());
+    if previous.is_none() {
+        queue!(stdout, ResetColor, Clear(ClearType::All))?;
+    }
+
+    let (terminal_width, terminal_height) = terminal::size()?;
+    let width = console.width().min(u32::from(terminal_width));
+    let height = console.height().min(u32::from(terminal_height));
+
+    let mut colors: Option<(Rgba, Rgba)> = None;
+    let mut cursor: Option<(u32, u32)> = None;
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * console.width() + x) as usize;
+            let cell = &console.cells()[index];
+            if previous.map(|previous| &previous.cells()[index]) == Some(cell) {
+                continue;
+            }
+
+            if cursor != Some((x, y)) {
+                queue!(stdout, MoveTo(x as u16, y as u16))?;
+            }
+            let cell_colors = (cell.fore, cell.back);
+            if colors != Some(cell_colors) {
+                queue!(
+                    stdout,
+                    SetForegroundColor(color_mode.to_color(cell.fore)),
+                    SetBackgroundColor(color_mode.to_color(cell.back)),
+                )?;
+                colors = Some(cell_colors);
+            }
+            queue!(stdout, Print(printable_glyph(cell)))?;
+            cursor = Some((x + 1, y));
+        }
+    }
+
+    queue!(stdout, ResetColor)?;
+    stdout.flush()
+}
+
+/// Control characters would move the cursor: they are drawn as spaces.
+fn printable_glyph(cell: &Cell) -> char {
+    if cell.glyph.is_control() {
+        ' '
+    } else {
+        cell.glyph
+    }
+}
+
+/// Restores the terminal when dropped. Kept in the world as a non-send resource until the
+/// app exits.
+struct TerminalGuard {
+    keyboard_enhancement: bool,
+    mouse: bool,
+    /// Puts back the panic hook replaced by [`TerminalGuard::enter`].
+    restore_panic_hook: Option<Box<dyn FnOnce()>>,
+}
+
+impl TerminalGuard {
+    fn enter(stdout: &mut BufWriter<Stdout>, mouse: bool) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let keyboard_enhancement = supports_keyboard_enhancement().unwrap_or(false);
+
+        queue!(stdout, EnterAlternateScreen, Hide, EnableFocusChange)?;
+        if mouse {
+            queue!(stdout, EnableMouseCapture)?;
+        }
+        if keyboard_enhancement {
+            queue!(
+                stdout,
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                )
+            )?;
+        }
+        stdout.flush()?;
+
+        // Restore the terminal before the panic message is printed.
+        let previous_hook = Arc::new(std::panic::take_hook());
+        let panic_hook = previous_hook.clone();
+        std::panic::set_hook(Box::new(move |info| {
+            restore(&mut io::stdout(), keyboard_enhancement, mouse);
+            panic_hook(info);
+        }));
+        let restore_panic_hook = move || {
+            // Dropping our hook releases its handle on the previous one.
+            drop(std::panic::take_hook());
+            if let Ok(previous_hook) = Arc::try_unwrap(previous_hook) {
+                std::panic::set_hook(previous_hook);
+            }
+        };
+
+        Ok(Self {
+            keyboard_enhancement,
+            mouse,
+            restore_panic_hook: Some(Box::new(restore_panic_hook)),
+        })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore(&mut io::stdout(), self.keyboard_enhancement, self.mouse);
+        // The panic hook cannot be changed while panicking, it is still needed then anyway.
+        if !std::thread::panicking() {
+            if let Some(restore_panic_hook) = self.restore_panic_hook.take() {
+                restore_panic_hook();
+            }
+        }
+    }
+}
+
+/// Leaves the alternate screen and the raw mode. Errors are ignored: there is nothing left
+/// to do about them.
+fn restore(stdout: &mut impl Write, keyboard_enhancement: bool, mouse: bool) {
+    if keyboard_enhancement {
+        let _ = queue!(stdout, PopKeyboardEnhancementFlags);
+    }
+    if mouse {
+        let _ = queue!(stdout, DisableMouseCapture);
+    }
+    let _ = queue!(
+        stdout,
+        DisableFocusChange,
+        ResetColor,
+        Show,
+        LeaveAlternateScreen
+    );
+    let _ = stdout.flush();
+    let _ = terminal::disable_raw_mode();
+}
@@ -0,0 +1,34 @@
+//! In-memory console.
+
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::Resource;
+use brltk_common::CellBuffer;
+
+/// The root console of the headless backend. Draw on it through the
+/// [`ConsoleApi`](brltk_common::ConsoleApi) trait.
+#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+pub struct HeadlessConsole(pub CellBuffer);
+
+impl HeadlessConsole {
+    /// Create a console filled with default cells.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self(CellBuffer::new(width, height))
+    }
+}
+
+impl Deref for HeadlessConsole {
+    type Target = CellBuffer;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HeadlessConsole {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
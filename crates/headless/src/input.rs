@@ -0,0 +1,134 @@
+//! Scripted input for the headless backend.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::{KeyCode, MouseButton, ResMut, Resource};
+use brltk_common::ConsoleInput;
+
+/// The input events of a single frame of an [`InputScript`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ScriptedFrame {
+    text: String,
+    close_requested: bool,
+    keys_pressed: Vec<KeyCode>,
+    keys_released: Vec<KeyCode>,
+    mouse_position: Option<(f32, f32)>,
+    mouse_buttons_pressed: Vec<MouseButton>,
+    mouse_buttons_released: Vec<MouseButton>,
+}
+
+impl ScriptedFrame {
+    /// Create a frame without any input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Press the given key. It stays down until it is released.
+    pub fn press(mut self, key: KeyCode) -> Self {
+        self.keys_pressed.push(key);
+        self
+    }
+
+    /// Release the given key.
+    pub fn release(mut self, key: KeyCode) -> Self {
+        self.keys_released.push(key);
+        self
+    }
+
+    /// Type the given text.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text.push_str(&text.into());
+        self
+    }
+
+    /// Move the mouse to the given position in console cells coordinates.
+    pub fn mouse_to(mut self, x: f32, y: f32) -> Self {
+        self.mouse_position = Some((x, y));
+        self
+    }
+
+    /// Press the given mouse button. It stays down until it is released.
+    pub fn mouse_press(mut self, mouse_button: MouseButton) -> Self {
+        self.mouse_buttons_pressed.push(mouse_button);
+        self
+    }
+
+    /// Release the given mouse button.
+    pub fn mouse_release(mut self, mouse_button: MouseButton) -> Self {
+        self.mouse_buttons_released.push(mouse_button);
+        self
+    }
+
+    /// Request the application to close.
+    pub fn close_request(mut self) -> Self {
+        self.close_requested = true;
+        self
+    }
+}
+
+/// A queue of [`ScriptedFrame`]s, one of which is played at the start of every frame.
+/// Once the script is empty, frames have no input.
+#[derive(Default, Debug, Clone, Resource)]
+pub struct InputScript(VecDeque<ScriptedFrame>);
+
+impl InputScript {
+    /// Queue a frame at the end of the script.
+    pub fn push(&mut self, frame: ScriptedFrame) -> &mut Self {
+        self.0.push_back(frame);
+        self
+    }
+
+    /// Queue the given number of frames without any input.
+    pub fn wait(&mut self, frames: usize) -> &mut Self {
+        self.0
+            .extend(std::iter::repeat_with(ScriptedFrame::default).take(frames));
+        self
+    }
+
+    /// Returns true if every frame of the script has been played.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of frames left to play.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl FromIterator<ScriptedFrame> for InputScript {
+    fn from_iter<T: IntoIterator<Item = ScriptedFrame>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Plays the next frame of the [`InputScript`] into the [`ConsoleInput`].
+pub(crate) fn apply_input_script(mut script: ResMut<InputScript>, mut input: ResMut<ConsoleInput>) {
+    let frame = script.0.pop_front().unwrap_or_default();
+
+    input.on_frame();
+    frame
+        .keys_pressed
+        .into_iter()
+        .for_each(|key| input.press_key(key));
+    frame
+        .keys_released
+        .into_iter()
+        .for_each(|key| input.release_key(key));
+    frame
+        .mouse_buttons_pressed
+        .into_iter()
+        .for_each(|button| input.press_mouse_button(button));
+    frame
+        .mouse_buttons_released
+        .into_iter()
+        .for_each(|button| input.release_mouse_button(button));
+
+    input.push_text(&frame.text);
+    if let Some(mouse_position) = frame.mouse_position {
+        input.set_mouse_position(mouse_position);
+    }
+    if frame.close_requested {
+        input.request_close();
+    }
+}
@@ -0,0 +1,88 @@
+//! `brltk_headless` is a backend without any window, keeping the console in memory.
+//!
+//! It is meant to run full game loops where no display is available, like in `cargo test`
+//! or on a dedicated server process. Input is provided through an [`InputScript`] and read
+//! from the [`ConsoleInput`](brltk_common::ConsoleInput) resource.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::time::Duration;
+
+use bevy::prelude::{App as BevyApp, CoreStage, IntoSystemDescriptor};
+use brltk_common::{Backend, BackendCapabilities, ConsoleInput, ConsoleInputSystem};
+
+mod console;
+mod input;
+mod runner;
+
+pub use crate::{console::*, input::*, runner::*};
+
+/// The headless backend.
+#[derive(Debug, Clone)]
+pub struct HeadlessBackend {
+    /// The width of the [`HeadlessConsole`] in cells.
+    pub console_width: u32,
+
+    /// The height of the [`HeadlessConsole`] in cells.
+    pub console_height: u32,
+
+    /// The duration of a frame when the app is run. `None` runs the frames as fast as
+    /// possible.
+    pub frame_time: Option<Duration>,
+
+    /// The number of frames to run before exiting. `None` runs until an
+    /// [`AppExit`](bevy::app::AppExit) event is sent.
+    pub max_frames: Option<u64>,
+}
+
+impl Default for HeadlessBackend {
+    fn default() -> Self {
+        Self {
+            console_width: 80,
+            console_height: 45,
+            frame_time: Some(Duration::from_secs(1) / 60),
+            max_frames: None,
+        }
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn build(&self, app: &mut BevyApp) {
+        app.insert_resource(HeadlessConsole::new(
+            self.console_width,
+            self.console_height,
+        ))
+        .init_resource::<ConsoleInput>()
+        .init_resource::<InputScript>()
+        .add_system_to_stage(
+            CoreStage::First,
+            apply_input_script.label(ConsoleInputSystem),
+        );
+
+        let Self {
+            frame_time,
+            max_frames,
+            ..
+        } = self.clone();
+
+        app.set_runner(move |app| headless_runner(app, frame_time, max_frames));
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            name: "headless",
+            subcell: false,
+            alpha_blending: false,
+            true_color: true,
+            named_colors: false,
+            layers: false,
+            sparse_layers: false,
+            multiple_fonts: false,
+            runtime_font_change: false,
+            runtime_resize: false,
+            borders: false,
+            windowed: false,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Fixed step runner for the headless backend.
+
+use std::time::Duration;
+
+use bevy::{
+    app::{App, AppExit},
+    ecs::event::{Events, ManualEventReader},
+    utils::Instant,
+};
+
+/// Runs the app without any window, one frame every `frame_time`.
+///
+/// The runner stops once an [`AppExit`] event is sent, or once `max_frames` frames have
+/// been run. In tests, call [`run_frames`] instead to inspect the app once it stopped, or
+/// [`App::update`] directly for full control over the frames.
+pub fn headless_runner(mut app: App, frame_time: Option<Duration>, max_frames: Option<u64>) {
+    run_frames(&mut app, frame_time, max_frames);
+}
+
+/// Runs the frames of the app like [`headless_runner`], and returns the number of frames
+/// run. The app is left as it was after its last frame.
+pub fn run_frames(app: &mut App, frame_time: Option<Duration>, max_frames: Option<u64>) -> u64 {
+    let mut app_exit_event_reader = ManualEventReader::<AppExit>::default();
+    let mut frames = 0;
+
+    while max_frames != Some(frames) {
+        let start = Instant::now();
+
+        app.update();
+        frames += 1;
+
+        if let Some(app_exit_events) = app.world.get_resource::<Events<AppExit>>() {
+            if app_exit_event_reader.iter(app_exit_events).last().is_some() {
+                break;
+            }
+        }
+
+        if let Some(remaining) =
+            frame_time.and_then(|frame_time| frame_time.checked_sub(start.elapsed()))
+        {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    frames
+}
@@ -0,0 +1,87 @@
+use bevy::{
+    app::AppExit,
+    prelude::{App, EventWriter, IntoSystemDescriptor, KeyCode, Res, ResMut, Resource},
+};
+use brltk_common::{assert_console_snapshot, Backend, ConsoleApi, ConsoleInput, TextAlign};
+use brltk_headless::{run_frames, HeadlessBackend, HeadlessConsole, InputScript, ScriptedFrame};
+
+#[derive(Resource)]
+struct Player {
+    x: i32,
+    y: i32,
+}
+
+fn walk(input: Res<ConsoleInput>, mut player: ResMut<Player>) {
+    if input.key(KeyCode::Right) {
+        player.x += 1;
+    }
+    if input.key_pressed(KeyCode::Down) {
+        player.y += 1;
+    }
+}
+
+fn quit(input: Res<ConsoleInput>, mut app_exit: EventWriter<AppExit>) {
+    if input.key_pressed(KeyCode::Escape) {
+        app_exit.send(AppExit);
+    }
+}
+
+fn render(player: Res<Player>, mut console: ResMut<HeadlessConsole>) {
+    console.clear(None, Some((0, 0, 0, 255)), Some('.'));
+    console.put_glyph(player.x, player.y, '@');
+    console.print(9, 3, "end", TextAlign::Right, None, None);
+}
+
+fn game(script: InputScript) -> App {
+    let mut app = App::new();
+    HeadlessBackend {
+        console_width: 10,
+        console_height: 4,
+        frame_time: None,
+        max_frames: Some(100),
+    }
+    .build(&mut app);
+    app.insert_resource(script)
+        .insert_resource(Player { x: 1, y: 1 })
+        .add_system(walk)
+        .add_system(quit)
+        .add_system(render.after(walk));
+    app
+}
+
+#[test]
+fn runs_until_app_exit() {
+    let mut script = InputScript::default();
+    script
+        .push(ScriptedFrame::new().press(KeyCode::Right))
+        .wait(2)
+        .push(ScriptedFrame::new().release(KeyCode::Right))
+        .push(ScriptedFrame::new().press(KeyCode::Down))
+        .wait(1)
+        .push(ScriptedFrame::new().press(KeyCode::Escape));
+    let mut app = game(script);
+
+    let frames = run_frames(&mut app, None, Some(100));
+
+    assert_eq!(frames, 7);
+    assert!(app.world.resource::<InputScript>().is_empty());
+    let console = app.world.resource::<HeadlessConsole>();
+    assert_eq!(console.size(), (10, 4));
+    let rows: Vec<_> = (0..4).filter_map(|y| console.row_text(y)).collect();
+    assert_eq!(
+        rows,
+        ["..........", "..........", "....@.....", ".......end"]
+    );
+    assert_console_snapshot("game_loop_exit", &**console);
+}
+
+#[test]
+fn stops_after_max_frames() {
+    let mut app = game(InputScript::default());
+
+    assert_eq!(run_frames(&mut app, None, Some(3)), 3);
+    assert_eq!(
+        app.world.resource::<HeadlessConsole>().row_text(1).unwrap(),
+        ".@........"
+    );
+}
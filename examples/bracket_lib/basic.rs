@@ -14,6 +14,7 @@ fn main() {
             going_down: true,
         })
         .add_system(tick)
+        .add_system(swap_palette)
         .run();
 }
 
@@ -76,3 +77,14 @@ fn tick(ctx: Res<BracketContext>, mut state: ResMut<State>) {
         None,
     );
 }
+
+// Press space to switch to a high contrast palette.
+fn swap_palette(keys: Res<Input<KeyCode>>, mut set_palette: EventWriter<SetPalette>) {
+    if keys.just_pressed(KeyCode::Space) {
+        set_palette.send(SetPalette(
+            ColorPalette::new()
+                .with_color("blue", RGBA::from_u8(0, 114, 178, 255))
+                .with_color("pink", RGBA::from_u8(230, 159, 0, 255)),
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::{app::AppExit, prelude::*};
+use brltk::prelude::{
+    brltk_crossterm::{CrosstermBackend, CrosstermConsole},
+    BRLTKPlugin, ConsoleApi, ConsoleInput, TextAlign,
+};
+
+#[derive(Default, Resource)]
+struct Player {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    App::new()
+        .add_plugin(BRLTKPlugin::with_backend(CrosstermBackend::default()))
+        .insert_resource(Player { x: 2, y: 2 })
+        .add_system(input)
+        .add_system(render.after(input))
+        .run();
+}
+
+fn input(
+    input: Res<ConsoleInput>,
+    console: Res<CrosstermConsole>,
+    mut player: ResMut<Player>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if input.key_pressed(KeyCode::Left) {
+        player.x -= 1;
+    }
+    if input.key_pressed(KeyCode::Right) {
+        player.x += 1;
+    }
+    if input.key_pressed(KeyCode::Up) {
+        player.y -= 1;
+    }
+    if input.key_pressed(KeyCode::Down) {
+        player.y += 1;
+    }
+    if input.mouse_pressed(MouseButton::Left) {
+        (player.x, player.y) = input.mouse_cell();
+    }
+    player.x = player.x.clamp(0, console.width() as i32 - 1);
+    player.y = player.y.clamp(0, console.height() as i32 - 1);
+
+    if input.key_pressed(KeyCode::Escape) {
+        app_exit.send(AppExit);
+    }
+}
+
+fn render(player: Res<Player>, mut console: ResMut<CrosstermConsole>) {
+    let (width, height) = (console.width() as i32, console.height() as i32);
+    console.clear(Some((96, 96, 96, 255)), Some((16, 16, 32, 255)), Some('.'));
+    console.put_glyph(player.x, player.y, '@');
+    console.set_fore(player.x, player.y, (255, 192, 64, 255));
+    console.print(
+        width / 2,
+        height - 1,
+        "arrows or click to move, escape to exit",
+        TextAlign::Center,
+        Some((255, 255, 255, 255)),
+        None,
+    );
+}
@@ -4,7 +4,7 @@ use brltk::prelude::{
     bevy_doryen::{
         DoryenAppOptions, DoryenBackend, DoryenInput, RenderSystemExt, RootConsole, VirtualScanCode,
     },
-    BRLTKPlugin,
+    BRLTKPlugin, Repeat,
 };
 
 const CONSOLE_WIDTH: u32 = 80;
@@ -59,6 +59,7 @@ fn main() {
                 intercept_close_request: false,
                 max_fps: 60,
             },
+            key_repeat: Some(Repeat::default()),
             ..Default::default()
         }))
         .add_startup_system(init)
@@ -102,14 +103,14 @@ fn input(
         .get_component_mut::<Position<i32>>(entities.player)
         .unwrap();
 
-    if input.key(VirtualScanCode::Left) {
+    if input.key_pressed(VirtualScanCode::Left) {
         player_position.x = (player_position.x - 1).max(1);
-    } else if input.key(VirtualScanCode::Right) {
+    } else if input.key_pressed(VirtualScanCode::Right) {
         player_position.x = (player_position.x + 1).min(CONSOLE_WIDTH as i32 - 2);
     }
-    if input.key(VirtualScanCode::Up) {
+    if input.key_pressed(VirtualScanCode::Up) {
         player_position.y = (player_position.y - 1).max(1);
-    } else if input.key(VirtualScanCode::Down) {
+    } else if input.key_pressed(VirtualScanCode::Down) {
         player_position.y = (player_position.y + 1).min(CONSOLE_HEIGHT as i32 - 2);
     }
 
@@ -1,7 +1,8 @@
-use bevy::{app::AppExit, prelude::*};
+use bevy::prelude::*;
 use bevy_doryen::{
     doryen::{self, Color, TextAlign},
-    DoryenAppOptions, DoryenBackend, DoryenInput, RenderSystemExt, RootConsole,
+    CloseConfirmation, CloseRequestPolicy, DoryenAppOptions, DoryenBackend, DoryenInput,
+    RenderSystemExt, RootConsole,
 };
 use brltk::prelude::BRLTKPlugin;
 
@@ -9,13 +10,12 @@ fn main() {
     App::new()
         .add_plugin(BRLTKPlugin::with_backend(DoryenBackend {
             app_options: DoryenAppOptions {
-                intercept_close_request: true,
                 window_title: String::from("alpha test"),
                 ..Default::default()
             },
+            close_request_policy: CloseRequestPolicy::Confirm,
             ..Default::default()
         }))
-        .init_resource::<CloseRequested>()
         .add_system(process_input)
         .add_doryen_render_system(render)
         .run();
@@ -23,44 +23,22 @@ fn main() {
 
 const WHITE: Color = (255, 255, 255, 255);
 
-#[derive(Default, Resource)]
-struct CloseRequested(bool);
-
-fn process_input(
-    input: Res<DoryenInput>,
-    mut close_requested: ResMut<CloseRequested>,
-    mut app_exit: EventWriter<AppExit>,
-) {
-    if close_requested.0 {
-        if input.key(doryen::ScanCode::Y) {
-            app_exit.send(AppExit);
-        } else if input.key(doryen::ScanCode::N) {
-            close_requested.0 = false;
-        }
-    } else if input.key(doryen::ScanCode::Escape) || input.close_requested() {
-        close_requested.0 = true;
+fn process_input(input: Res<DoryenInput>, mut confirmation: ResMut<CloseConfirmation>) {
+    // The close requests open the confirmation dialog, open it on Escape too. The dialog
+    // takes the keyboard while it is open, so the Escape closing it does not reopen it.
+    if !confirmation.is_open() && input.key_pressed(doryen::ScanCode::Escape) {
+        confirmation.open();
     }
 }
 
-fn render(mut root_console: ResMut<RootConsole>, close_requested: Res<CloseRequested>) {
+fn render(mut root_console: ResMut<RootConsole>) {
     root_console.clear(None, None, Some(' ' as u16));
-    if close_requested.0 {
-        root_console.print(
-            5,
-            5,
-            "Exit game ? (press Y or N)",
-            TextAlign::Left,
-            Some(WHITE),
-            None,
-        );
-    } else {
-        root_console.print(
-            5,
-            5,
-            "Press ESC to exit",
-            TextAlign::Left,
-            Some(WHITE),
-            None,
-        );
-    }
+    root_console.print(
+        5,
+        5,
+        "Press ESC to exit",
+        TextAlign::Left,
+        Some(WHITE),
+        None,
+    );
 }
@@ -21,15 +21,15 @@ fn main() {
 struct SkullImage {
     angle: f32,
     scale_time: f32,
-    skull: DoryenImage,
+    skull: Handle<DoryenImage>,
 }
 
-impl Default for SkullImage {
-    fn default() -> Self {
+impl FromWorld for SkullImage {
+    fn from_world(world: &mut World) -> Self {
         Self {
             angle: 0.0,
             scale_time: 0.0,
-            skull: DoryenImage::new("skull.png"),
+            skull: world.resource::<AssetServer>().load("skull.png"),
         }
     }
 }
@@ -39,12 +39,18 @@ fn update(mut skull: ResMut<SkullImage>) {
     skull.scale_time += 0.01;
 }
 
-fn render(mut root_console: ResMut<RootConsole>, mut skull: ResMut<SkullImage>) {
+fn render(
+    mut root_console: ResMut<RootConsole>,
+    skull: Res<SkullImage>,
+    mut images: ResMut<Assets<DoryenImage>>,
+) {
     let root_console = &mut **root_console;
-    let skull = &mut *skull;
     let scale = skull.scale_time.cos();
     root_console.clear(None, Some((0, 0, 0, 255)), None);
-    skull.skull.blit_ex(
+    let Some(skull_image) = images.get_mut(&skull.skull) else {
+        return;
+    };
+    skull_image.blit_ex(
         root_console,
         (root_console.get_width() / 2) as f32,
         (root_console.get_height() / 2) as f32,
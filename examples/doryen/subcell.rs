@@ -20,22 +20,26 @@ fn main() {
 
 #[derive(Resource)]
 struct SkullImage {
-    skull: DoryenImage,
+    skull: Handle<DoryenImage>,
 }
 
-impl Default for SkullImage {
-    fn default() -> Self {
+impl FromWorld for SkullImage {
+    fn from_world(world: &mut World) -> Self {
         Self {
-            skull: DoryenImage::new("skull.png"),
+            skull: world.resource::<AssetServer>().load("skull.png"),
         }
     }
 }
 
-fn render(mut root_console: ResMut<RootConsole>, mut skull: ResMut<SkullImage>) {
+fn render(
+    mut root_console: ResMut<RootConsole>,
+    skull: Res<SkullImage>,
+    mut images: ResMut<Assets<DoryenImage>>,
+) {
     root_console.clear(None, Some((0, 0, 0, 255)), None);
-    skull
-        .skull
-        .blit_2x(&mut root_console, 23, 0, 0, 0, None, None, None);
+    if let Some(skull_image) = images.get_mut(&skull.skull) {
+        skull_image.blit_2x(&mut root_console, 23, 0, 0, 0, None, None, None);
+    }
     root_console.print(
         40,
         4,
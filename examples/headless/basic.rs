@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use brltk::prelude::{
+    brltk_headless::{HeadlessBackend, HeadlessConsole, InputScript, ScriptedFrame},
+    BRLTKPlugin, ConsoleApi, ConsoleInput, TextAlign,
+};
+
+#[derive(Default, Resource)]
+struct Player {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let mut script = InputScript::default();
+    script
+        .push(ScriptedFrame::new().press(KeyCode::Right))
+        .wait(4)
+        .push(ScriptedFrame::new().release(KeyCode::Right))
+        .push(ScriptedFrame::new().press(KeyCode::Down))
+        .push(ScriptedFrame::new().release(KeyCode::Down));
+
+    App::new()
+        .add_plugin(BRLTKPlugin::with_backend(HeadlessBackend {
+            console_width: 20,
+            console_height: 5,
+            frame_time: None,
+            max_frames: Some(10),
+        }))
+        .insert_resource(script)
+        .insert_resource(Player { x: 2, y: 1 })
+        .add_system(input)
+        .add_system(render.after(input))
+        .add_system_to_stage(CoreStage::Last, print_console)
+        .run();
+}
+
+fn input(input: Res<ConsoleInput>, mut player: ResMut<Player>) {
+    if input.key(KeyCode::Right) {
+        player.x += 1;
+    }
+    if input.key_pressed(KeyCode::Down) {
+        player.y += 1;
+    }
+}
+
+fn render(player: Res<Player>, mut console: ResMut<HeadlessConsole>) {
+    console.clear(None, Some((0, 0, 0, 255)), Some('.'));
+    console.put_glyph(player.x, player.y, '@');
+    console.print(10, 4, "headless", TextAlign::Center, None, None);
+}
+
+fn print_console(console: Res<HeadlessConsole>) {
+    for y in 0..console.height() as i32 {
+        println!("{}", console.row_text(y).unwrap_or_default());
+    }
+    println!();
+}
@@ -5,6 +5,8 @@
 //!
 //! Usage:
 //! ```no_run
+//! # #[cfg(feature = "doryen")]
+//! # fn main() {
 //! use bevy::prelude::App;
 //! use brltk::prelude::{
 //!     bevy_doryen::{
@@ -45,6 +47,9 @@
 //! # fn init() { }
 //! # fn input() { }
 //! # fn render() { }
+//! # }
+//! # #[cfg(not(feature = "doryen"))]
+//! # fn main() {}
 //! ```
 //!
 //! [Bevy]: https://bevyengine.org/
@@ -55,6 +60,7 @@
 #![warn(missing_docs)]
 
 mod plugin;
+mod registry;
 
 /// Crate prelude.
 pub mod prelude {
@@ -66,6 +72,10 @@ pub mod prelude {
     pub use bevy_bracket_lib;
     #[cfg(feature = "doryen")]
     pub use bevy_doryen;
+    #[cfg(feature = "crossterm")]
+    pub use brltk_crossterm;
+    #[cfg(feature = "headless")]
+    pub use brltk_headless;
 
     pub use brltk_common::*;
     // Yendor
@@ -73,5 +83,5 @@ pub mod prelude {
     pub use yendor_lib::prelude::*;
 
     // Internal Prelude
-    pub use crate::plugin::*;
+    pub use crate::{plugin::*, registry::*};
 }
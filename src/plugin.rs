@@ -1,42 +1,180 @@
-use bevy::prelude::{App as BevyApp, Msaa, Plugin};
-use bevy_doryen::DoryenBackend;
+use std::sync::Arc;
+
+use bevy::{
+    app::AppExit,
+    ecs::event::ManualEventReader,
+    prelude::{App as BevyApp, CoreStage, Events, IntoSystemDescriptor, Msaa, Plugin, World},
+};
 
 use crate::prelude::*;
 
 /// The Bevy Roguelike Toolkit plugin.
+///
+/// With the `hot_reload` feature, the asset folder is watched and the fonts and images loaded
+/// through the [`AssetServer`](bevy::asset::AssetServer) are reloaded when their files are
+/// modified. Add the `AssetPlugin`, for instance with the `DefaultPlugins`, before this
+/// plugin. The fonts embedded in `bevy_ascii_terminal` cannot be reloaded.
 pub struct BRLTKPlugin {
     /// The Roguelike [`Backend`] to use.
-    backend: Box<dyn Backend>,
+    backend: Arc<dyn Backend>,
 }
 
+/// Uses the backend selected by the command line, the environment or the config file, as
+/// [`BRLTKPlugin::from_registry`] with the default [`BackendRegistry`]. Without a selection,
+/// uses the first enabled backend, in the order `doryen`, `bracket_lib`, `ascii_terminal`,
+/// `crossterm` and `headless`, with its default settings.
+///
+/// # Panics
+///
+/// Panics if the selected backend is not enabled, or if the config file cannot be read.
+#[cfg(any(
+    feature = "doryen",
+    feature = "bracket_lib",
+    feature = "ascii_terminal",
+    feature = "crossterm",
+    feature = "headless"
+))]
 impl Default for BRLTKPlugin {
-    #[allow(clippy::box_default)]
     fn default() -> Self {
         Self {
-            backend: Box::new(DoryenBackend::default()),
+            backend: BackendRegistry::default()
+                .select()
+                .unwrap_or_else(|error| panic!("Failed to select the backend: {error}"))
+                .into(),
         }
     }
 }
 
 impl BRLTKPlugin {
-    /// Create a new [`BRLTKPlugin`].
+    /// Create a new [`BRLTKPlugin`] with the selected backend, see [`BRLTKPlugin::default`].
+    #[cfg(any(
+        feature = "doryen",
+        feature = "bracket_lib",
+        feature = "ascii_terminal",
+        feature = "crossterm",
+        feature = "headless"
+    ))]
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a new [`BRLTKPlugin`] with the backend selected by the command line, the
+    /// environment or the config file. See [`BackendRegistry`].
+    pub fn from_registry(registry: &BackendRegistry) -> Result<Self, BackendSelectionError> {
+        Ok(Self {
+            backend: registry.select()?.into(),
+        })
+    }
+
     /// Create a new [`BRLTKPlugin`] with the given [`Backend`].
     pub fn with_backend(backend: impl Backend + 'static) -> Self {
         Self {
-            backend: Box::new(backend),
+            backend: Arc::new(backend),
         }
     }
 }
 
 impl Plugin for BRLTKPlugin {
     fn build(&self, app: &mut BevyApp) {
-        app.insert_resource(Msaa { samples: 1 });
+        app.insert_resource(Msaa { samples: 1 })
+            .insert_resource(self.backend.capabilities());
+
+        // Watch the asset folder before the backend loads its fonts.
+        #[cfg(feature = "hot_reload")]
+        if let Some(asset_server) = app.world.get_resource::<bevy::asset::AssetServer>() {
+            if let Err(error) = asset_server.asset_io().watch_for_changes() {
+                bevy::log::warn!("Failed to watch the asset folder: {error}");
+            }
+        }
 
         // Build the backend.
         self.backend.build(app);
+
+        // Forward the lifecycle events to the backend.
+        let backend = self.backend.clone();
+        let mut lifecycle_event_reader = ManualEventReader::<AppLifecycle>::default();
+        app.add_plugin(LifecyclePlugin).add_system_to_stage(
+            CoreStage::Last,
+            (move |world: &mut World| {
+                let events: Vec<_> = lifecycle_event_reader
+                    .iter(world.resource::<Events<AppLifecycle>>())
+                    .copied()
+                    .collect();
+                for event in events {
+                    backend.on_lifecycle(world, event);
+                }
+                if world.resource::<LifecycleState>().is_exiting()
+                    && !world.resource::<Events<AppExit>>().is_empty()
+                {
+                    backend.cleanup(world);
+                }
+            })
+            .after(LifecycleSystem),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Resource;
+
+    use super::*;
+
+    /// The lifecycle calls received by the [`RecordingBackend`], `None` for the cleanup.
+    #[derive(Default, Resource)]
+    struct Calls(Vec<Option<AppLifecycle>>);
+
+    struct RecordingBackend;
+
+    impl Backend for RecordingBackend {
+        fn build(&self, app: &mut BevyApp) {
+            app.init_resource::<Calls>();
+        }
+
+        fn capabilities(&self) -> BackendCapabilities {
+            BackendCapabilities {
+                name: "recording",
+                subcell: false,
+                alpha_blending: false,
+                true_color: false,
+                named_colors: false,
+                layers: false,
+                sparse_layers: false,
+                multiple_fonts: false,
+                runtime_font_change: false,
+                runtime_resize: false,
+                borders: false,
+                windowed: false,
+            }
+        }
+
+        fn on_lifecycle(&self, world: &mut World, event: AppLifecycle) {
+            world.resource_mut::<Calls>().0.push(Some(event));
+        }
+
+        fn cleanup(&self, world: &mut World) {
+            world.resource_mut::<Calls>().0.push(None);
+        }
+    }
+
+    #[test]
+    fn forwards_the_lifecycle_to_the_backend() {
+        let mut app = BevyApp::new();
+        app.add_plugin(BRLTKPlugin::with_backend(RecordingBackend));
+
+        app.update();
+        assert_eq!(app.world.resource::<Calls>().0, [Some(AppLifecycle::Started)]);
+
+        app.world.send_event(AppExit);
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.resource::<Calls>().0,
+            [
+                Some(AppLifecycle::Started),
+                Some(AppLifecycle::Exiting),
+                None
+            ]
+        );
     }
 }
@@ -0,0 +1,276 @@
+use std::{
+    ffi::OsString,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::prelude::Backend;
+
+/// The environment variable naming the backend to use.
+pub const BACKEND_ENV_VAR: &str = "BRLTK_BACKEND";
+
+/// The command line flag naming the backend to use, as `--backend name` or `--backend=name`.
+pub const BACKEND_FLAG: &str = "--backend";
+
+/// The config file read by default, relative to the working directory.
+pub const DEFAULT_CONFIG_PATH: &str = "brltk.ron";
+
+type BackendFactory = Box<dyn Fn() -> Box<dyn Backend> + Send + Sync>;
+
+/// The backends the game can run with, picked by name at startup.
+///
+/// The backend is picked, in order of priority, from:
+/// 1. the [`BACKEND_FLAG`] command line flag,
+/// 2. the [`BACKEND_ENV_VAR`] environment variable,
+/// 3. the `backend` field of the config file, `(backend: "bracket_lib")`, by default
+///    [`DEFAULT_CONFIG_PATH`],
+/// 4. the first registered backend.
+///
+/// The default registry holds every backend enabled by the crate features, with their
+/// default settings, named `doryen`, `bracket_lib`, `ascii_terminal`, `crossterm` and
+/// `headless`.
+/// Register a backend under the same name to replace its settings.
+///
+/// The backend cannot be swapped once the app is built: it owns the window and the runner.
+/// Switching backends requires restarting the game.
+pub struct BackendRegistry {
+    backends: Vec<(String, BackendFactory)>,
+    config_path: PathBuf,
+}
+
+impl Default for BackendRegistry {
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+
+        #[cfg(feature = "doryen")]
+        {
+            registry = registry.register("doryen", bevy_doryen::DoryenBackend::default);
+        }
+        #[cfg(feature = "bracket_lib")]
+        {
+            registry =
+                registry.register("bracket_lib", bevy_bracket_lib::BracketLibBackend::default);
+        }
+        #[cfg(feature = "ascii_terminal")]
+        {
+            registry = registry.register("ascii_terminal", || {
+                bevy_ascii_terminal::BevyAsciiTerminalBackend::default()
+                    .with_terminal(bevy_ascii_terminal::TerminalBundleBuilder::default())
+            });
+        }
+        #[cfg(feature = "crossterm")]
+        {
+            registry = registry.register("crossterm", brltk_crossterm::CrosstermBackend::default);
+        }
+        #[cfg(feature = "headless")]
+        {
+            registry = registry.register("headless", brltk_headless::HeadlessBackend::default);
+        }
+
+        registry
+    }
+}
+
+impl BackendRegistry {
+    /// Create a registry without any backend.
+    pub fn new() -> Self {
+        Self {
+            backends: Vec::new(),
+            config_path: PathBuf::from(DEFAULT_CONFIG_PATH),
+        }
+    }
+
+    /// Register a backend under the given name, replacing the backend with the same name.
+    pub fn register<B, F>(mut self, name: impl Into<String>, factory: F) -> Self
+    where
+        B: Backend + 'static,
+        F: Fn() -> B + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let factory: BackendFactory = Box::new(move || Box::new(factory()));
+        match self.backends.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, f)) => *f = factory,
+            None => self.backends.push((name, factory)),
+        }
+        self
+    }
+
+    /// Set the config file to read the backend name from.
+    pub fn with_config_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.config_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Returns an iterator over the names of the registered backends.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.backends.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Create the backend with the given name.
+    pub fn create(&self, name: &str) -> Option<Box<dyn Backend>> {
+        self.backends
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, factory)| factory())
+    }
+
+    /// Returns the name of the backend requested by the command line, the environment or
+    /// the config file, if any. A missing config file is not an error.
+    pub fn requested_backend(&self) -> Result<Option<String>, BackendSelectionError> {
+        if let Some(name) = backend_from_args(std::env::args_os()) {
+            return Ok(Some(name));
+        }
+
+        if let Ok(name) = std::env::var(BACKEND_ENV_VAR) {
+            return Ok(Some(name));
+        }
+
+        let config = match std::fs::read_to_string(&self.config_path) {
+            Ok(config) => config,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(BackendSelectionError::Io(self.config_path.clone(), e)),
+        };
+        let config: BackendConfig = ron::from_str(&config)
+            .map_err(|e| BackendSelectionError::Config(self.config_path.clone(), e))?;
+        Ok(config.backend)
+    }
+
+    /// Create the requested backend, or the first registered backend if none was requested.
+    pub fn select(&self) -> Result<Box<dyn Backend>, BackendSelectionError> {
+        match self.requested_backend()? {
+            Some(name) => self
+                .create(&name)
+                .ok_or_else(|| BackendSelectionError::Unknown {
+                    name,
+                    available: self.names().map(String::from).collect(),
+                }),
+            None => self.create_first().ok_or(BackendSelectionError::NoBackend),
+        }
+    }
+
+    /// Create the first registered backend.
+    pub(crate) fn create_first(&self) -> Option<Box<dyn Backend>> {
+        self.backends.first().map(|(_, factory)| factory())
+    }
+}
+
+/// The content of the config file.
+#[derive(Deserialize)]
+struct BackendConfig {
+    #[serde(default)]
+    backend: Option<String>,
+}
+
+/// Returns the backend named by the [`BACKEND_FLAG`] in the arguments, skipping the program
+/// name. The arguments that are not valid UTF-8 cannot name a backend and are skipped.
+fn backend_from_args(args: impl IntoIterator<Item = OsString>) -> Option<String> {
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg.to_str() else {
+            continue;
+        };
+        if arg == BACKEND_FLAG {
+            return args.next().and_then(|name| name.into_string().ok());
+        }
+        if let Some(name) = arg
+            .strip_prefix(BACKEND_FLAG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// An error raised while selecting a backend from a [`BackendRegistry`].
+#[derive(Debug)]
+pub enum BackendSelectionError {
+    /// No backend is registered, none of the backend features is enabled.
+    NoBackend,
+    /// The requested backend is not registered, or its feature is not enabled.
+    Unknown {
+        /// The name of the requested backend.
+        name: String,
+        /// The names of the registered backends.
+        available: Vec<String>,
+    },
+    /// The config file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The config file is not valid.
+    Config(PathBuf, ron::error::SpannedError),
+}
+
+impl fmt::Display for BackendSelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoBackend => write!(f, "no backend is enabled"),
+            Self::Unknown { name, available } => write!(
+                f,
+                "unknown backend `{name}`, expected one of: {}",
+                available.join(", ")
+            ),
+            Self::Io(path, e) => write!(f, "cannot read {}: {e}", path.display()),
+            Self::Config(path, e) => write!(f, "invalid config {}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BackendSelectionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        std::iter::once("game")
+            .chain(args.iter().copied())
+            .map(OsString::from)
+            .collect()
+    }
+
+    #[test]
+    fn backend_flag() {
+        assert_eq!(
+            backend_from_args(args(&["--backend", "crossterm"])),
+            Some("crossterm".to_string())
+        );
+        assert_eq!(
+            backend_from_args(args(&["-v", "--backend=headless", "--backend", "doryen"])),
+            Some("headless".to_string())
+        );
+    }
+
+    #[test]
+    fn no_backend_flag() {
+        assert_eq!(backend_from_args(args(&[])), None);
+        assert_eq!(backend_from_args(args(&["--backend"])), None);
+        assert_eq!(backend_from_args(args(&["--backends=doryen"])), None);
+        // The first argument is the program name.
+        assert_eq!(
+            backend_from_args(["--backend=doryen"].map(OsString::from)),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_non_utf8_arguments() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let invalid = || OsString::from_vec(vec![b'-', 0xff]);
+        let mut with_invalid = args(&["--backend=ascii_terminal"]);
+        with_invalid.insert(1, invalid());
+        assert_eq!(
+            backend_from_args(with_invalid),
+            Some("ascii_terminal".to_string())
+        );
+
+        let mut invalid_name = args(&["--backend"]);
+        invalid_name.push(invalid());
+        invalid_name.push("doryen".into());
+        assert_eq!(backend_from_args(invalid_name), None);
+    }
+}